@@ -1,3 +1,73 @@
+use std::env;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+/// Directory with the binaries shipped as app resources.
+const BUNDLED_BIN_DIR: &str = "resources/bin";
+
 fn main() {
+  // Selects the bundled binaries under resources/bin/<target-triple>/ at runtime
+  println!("cargo:rustc-env=EASYCLAW_TARGET_TRIPLE={}", env::var("TARGET").unwrap());
+  write_binary_manifest();
   tauri_build::build()
 }
+
+/// Generates `$OUT_DIR/binary_manifest.rs`: SHA-256 and size of every bundled binary,
+/// keyed by its path relative to `resources/bin` (e.g. `x86_64-unknown-linux-gnu/zeroclaw`).
+/// Included by `utils::checksum`.
+fn write_binary_manifest() {
+  println!("cargo:rerun-if-changed={}", BUNDLED_BIN_DIR);
+
+  let bin_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(BUNDLED_BIN_DIR);
+  let mut files = Vec::new();
+  collect_files(&bin_dir, &mut files).expect("failed to list bundled binaries");
+  files.sort();
+
+  let mut manifest = String::from("&[\n");
+  for path in files {
+    println!("cargo:rerun-if-changed={}", path.display());
+    let name = path
+      .strip_prefix(&bin_dir)
+      .unwrap()
+      .components()
+      .map(|c| c.as_os_str().to_string_lossy())
+      .collect::<Vec<_>>()
+      .join("/");
+    let (sha256, size) = hash_file(&path)
+      .unwrap_or_else(|e| panic!("failed to hash {}: {}", path.display(), e));
+    writeln!(manifest, "    ({:?}, {:?}, {}),", name, sha256, size).unwrap();
+  }
+  manifest.push(']');
+
+  let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("binary_manifest.rs");
+  fs::write(&out, manifest).expect("failed to write binary manifest");
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+  if !dir.is_dir() {
+    return Ok(());
+  }
+  for entry in fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.is_dir() {
+      collect_files(&path, files)?;
+    } else if !path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')) {
+      files.push(path);
+    }
+  }
+  Ok(())
+}
+
+fn hash_file(path: &Path) -> io::Result<(String, u64)> {
+  let mut hasher = Sha256::new();
+  let size = io::copy(&mut File::open(path)?, &mut hasher)?;
+  let hex = hasher.finalize().iter().fold(String::new(), |mut hex, byte| {
+    let _ = write!(hex, "{:02x}", byte);
+    hex
+  });
+  Ok((hex, size))
+}
@@ -0,0 +1,378 @@
+use std::time::Duration;
+
+use log::{info, warn};
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use tauri::ipc::Channel;
+use tauri::State;
+
+use crate::utils::chat_stream::{ChatRequestGuard, ChatRequests, StreamDecoder, StreamFormat, StreamItem};
+use crate::utils::context::ZeroClawEnv;
+use crate::utils::error::EasyClawError;
+use crate::utils::net::{gateway_endpoint, GatewayEndpoint};
+
+/// How long the gateway may stay silent, before the reply starts or between streamed
+/// pieces; model replies can take minutes.
+const CHAT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Fields of the webhook reply that may hold the assistant's text, in order of preference.
+const REPLY_FIELDS: [&str; 3] = ["reply", "response", "content"];
+
+/// Progress of a reply, sent to the UI over the request's channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ChatEvent {
+    /// The next piece of the reply. A gateway that doesn't stream sends the whole reply as one delta.
+    Delta { text: String },
+}
+
+/// Receives a reply's events as they arrive.
+pub type ChatSink<'a> = &'a (dyn Fn(ChatEvent) + Send + Sync);
+
+/// The finished reply.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChatReply {
+    /// The full reply text, or what had arrived when the request was cancelled.
+    pub content: String,
+    /// Whether the gateway streamed the reply.
+    pub streamed: bool,
+    pub cancelled: bool,
+}
+
+/// Sends a chat message to the gateway's `/webhook`, streaming the reply to `on_event`,
+/// so the webview never talks HTTP to the gateway itself. `request_id` is chosen by the
+/// frontend and can be passed to `cancel_chat_message`.
+#[tauri::command]
+pub async fn send_chat_message(
+    env: State<'_, ZeroClawEnv>,
+    requests: State<'_, ChatRequests>,
+    request_id: String,
+    message: String,
+    on_event: Channel<ChatEvent>,
+) -> Result<ChatReply, EasyClawError> {
+    let forward = |event: ChatEvent| {
+        if let Err(e) = on_event.send(event) {
+            warn!("[send_chat_message] Failed to send chat event: {}", e);
+        }
+    };
+    send(&env, &requests, &request_id, &message, &forward).await
+}
+
+/// Stops a running `send_chat_message`, which then returns what had arrived so far.
+/// Returns false if the request already finished.
+#[tauri::command]
+pub async fn cancel_chat_message(requests: State<'_, ChatRequests>, request_id: String) -> Result<bool, EasyClawError> {
+    info!("[cancel_chat_message] Cancelling chat request {}", request_id);
+    Ok(requests.cancel(&request_id))
+}
+
+/// Core of `send_chat_message`.
+pub async fn send(
+    env: &ZeroClawEnv,
+    requests: &ChatRequests,
+    request_id: &str,
+    message: &str,
+    on_event: ChatSink<'_>,
+) -> Result<ChatReply, EasyClawError> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err(EasyClawError::InvalidInput("Chat message is empty".to_string()));
+    }
+
+    let mut request = requests.register(request_id)?;
+    // Resolved per message: the gateway may have moved to another port
+    let endpoint = gateway_endpoint(&env.config_path()?)?;
+    info!("[send_chat_message] Sending request {} to {}/webhook", request_id, endpoint.url);
+    post_webhook(&endpoint, message, CHAT_TIMEOUT, &mut request, on_event).await
+}
+
+/// POSTs `{ "message": ... }` to the gateway's `/webhook`, asking for a streamed reply.
+/// Gateways that don't stream answer with a single JSON reply, sent on as one delta.
+async fn post_webhook(
+    endpoint: &GatewayEndpoint,
+    message: &str,
+    timeout: Duration,
+    request: &mut ChatRequestGuard,
+    on_event: ChatSink<'_>,
+) -> Result<ChatReply, EasyClawError> {
+    let client = reqwest::Client::builder()
+        .read_timeout(timeout)
+        .no_proxy()
+        .build()
+        .map_err(|e| EasyClawError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+
+    let pending = client
+        .post(format!("{}/webhook", endpoint.url))
+        .header(ACCEPT, "text/event-stream, application/json")
+        .json(&serde_json::json!({ "message": message, "stream": true }))
+        .send();
+    let mut response = tokio::select! {
+        response = pending => response.map_err(|e| request_error(endpoint, timeout, e))?,
+        _ = request.cancelled() => return Ok(ChatReply { content: String::new(), streamed: false, cancelled: true }),
+    };
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let rejection = rejection(status.as_u16(), &body);
+        warn!("[send_chat_message] {}", rejection);
+        return Err(rejection);
+    }
+
+    let content_type = response.headers().get(CONTENT_TYPE).and_then(|v| v.to_str().ok()).unwrap_or_default();
+    let Some(format) = StreamFormat::from_content_type(content_type) else {
+        let body = tokio::select! {
+            body = response.text() => body.map_err(|e| request_error(endpoint, timeout, e))?,
+            _ = request.cancelled() => return Ok(ChatReply { content: String::new(), streamed: false, cancelled: true }),
+        };
+        let body: JsonValue = serde_json::from_str(&body)
+            .map_err(|e| EasyClawError::Internal(format!("Gateway returned an invalid reply: {}", e)))?;
+        let content = normalize_reply(body);
+        on_event(ChatEvent::Delta { text: content.clone() });
+        return Ok(ChatReply { content, streamed: false, cancelled: false });
+    };
+
+    let mut decoder = StreamDecoder::new(format);
+    let mut content = String::new();
+    loop {
+        let chunk = tokio::select! {
+            chunk = response.chunk() => chunk.map_err(|e| request_error(endpoint, timeout, e))?,
+            _ = request.cancelled() => {
+                info!("[send_chat_message] Request cancelled after {} chars", content.chars().count());
+                return Ok(ChatReply { content, streamed: true, cancelled: true });
+            }
+        };
+        let (items, finished) = match chunk {
+            Some(chunk) => (decoder.push(&chunk), false),
+            None => (decoder.finish(), true),
+        };
+        for item in items {
+            match item {
+                StreamItem::Delta(text) => {
+                    content.push_str(&text);
+                    on_event(ChatEvent::Delta { text });
+                }
+                StreamItem::Error(detail) => {
+                    warn!("[send_chat_message] Gateway reported an error mid-stream: {}", detail);
+                    return Err(EasyClawError::GatewayRejected { status: None, detail });
+                }
+                StreamItem::Done => return Ok(ChatReply { content, streamed: true, cancelled: false }),
+            }
+        }
+        if finished {
+            return Ok(ChatReply { content, streamed: true, cancelled: false });
+        }
+    }
+}
+
+fn request_error(endpoint: &GatewayEndpoint, timeout: Duration, e: reqwest::Error) -> EasyClawError {
+    if e.is_timeout() {
+        EasyClawError::Timeout(format!(
+            "Gateway at {} did not reply within {}s",
+            endpoint.url,
+            timeout.as_secs()
+        ))
+    } else {
+        EasyClawError::GatewayUnreachable(format!("Gateway not reachable at {}: {}", endpoint.url, e))
+    }
+}
+
+/// The gateway's own `error` message if the body has one, otherwise the HTTP status.
+fn rejection(status: u16, body: &str) -> EasyClawError {
+    let message = serde_json::from_str::<JsonValue>(body)
+        .ok()
+        .and_then(|body| body.get("error").and_then(JsonValue::as_str).map(str::to_string));
+    match message {
+        Some(detail) => EasyClawError::GatewayRejected { status: None, detail },
+        None => EasyClawError::GatewayRejected {
+            status: Some(status),
+            detail: format!("Gateway returned HTTP {}", status),
+        },
+    }
+}
+
+/// Picks the reply text out of the shapes gateway versions answer with
+/// (`reply`, `response` or `content`); anything else is returned as JSON.
+fn normalize_reply(body: JsonValue) -> String {
+    REPLY_FIELDS
+        .iter()
+        .find_map(|field| body.get(field).and_then(JsonValue::as_str))
+        .map(str::to_string)
+        .unwrap_or_else(|| match body {
+            JsonValue::String(text) => text,
+            other => other.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{mpsc, Mutex};
+    use std::thread;
+    use tempfile::TempDir;
+
+    /// Serves a single HTTP response on a random local port, pointing a temporary root's
+    /// config.toml at it. The body is written piece by piece; unless `close` is set the
+    /// connection stays open until the client drops it. Yields the raw request received.
+    fn serve_once(
+        tmp: &TempDir,
+        status_line: &'static str,
+        content_type: &'static str,
+        pieces: &'static [&'static str],
+        close: bool,
+    ) -> (ZeroClawEnv, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request = read_request(&mut stream);
+            let head = format!("{}\r\nContent-Type: {}\r\nConnection: close\r\n\r\n", status_line, content_type);
+            stream.write_all(head.as_bytes()).unwrap();
+            for piece in pieces {
+                let _ = stream.write_all(piece.as_bytes());
+                let _ = stream.flush();
+                thread::sleep(Duration::from_millis(20));
+            }
+            let _ = tx.send(request);
+            if !close {
+                let _ = stream.read_to_end(&mut Vec::new());
+            }
+        });
+        (env_for_port(tmp, port), rx)
+    }
+
+    /// Reads the request headers and as much body as `Content-Length` announces.
+    fn read_request(stream: &mut impl Read) -> String {
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        loop {
+            let n = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if n == 0 || is_complete(&text) {
+                return text;
+            }
+        }
+    }
+
+    fn is_complete(request: &str) -> bool {
+        let Some(header_end) = request.find("\r\n\r\n") else {
+            return false;
+        };
+        let length = request[..header_end]
+            .lines()
+            .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length:")?.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        request.len() >= header_end + 4 + length
+    }
+
+    fn env_for_port(tmp: &TempDir, port: u16) -> ZeroClawEnv {
+        fs::write(tmp.path().join("config.toml"), format!("[gateway]\nport = {}\nhost = \"127.0.0.1\"\n", port)).unwrap();
+        ZeroClawEnv::with_root(tmp.path())
+    }
+
+    /// Sends a message, collecting the deltas it streams.
+    async fn send_collecting(env: &ZeroClawEnv, requests: &ChatRequests, message: &str) -> (Result<ChatReply, EasyClawError>, Vec<String>) {
+        let deltas = Mutex::new(Vec::new());
+        let sink = |ChatEvent::Delta { text }: ChatEvent| deltas.lock().unwrap().push(text);
+        let result = send(env, requests, "r1", message, &sink).await;
+        (result, deltas.into_inner().unwrap())
+    }
+
+    #[test]
+    fn test_normalize_reply_shapes() {
+        assert_eq!(normalize_reply(serde_json::json!({ "reply": "a" })), "a");
+        assert_eq!(normalize_reply(serde_json::json!({ "response": "b" })), "b");
+        assert_eq!(normalize_reply(serde_json::json!({ "content": "c", "model": "x" })), "c");
+        assert_eq!(normalize_reply(serde_json::json!("plain")), "plain");
+        assert_eq!(normalize_reply(serde_json::json!({ "other": 1 })), r#"{"other":1}"#);
+    }
+
+    #[tokio::test]
+    async fn test_send_falls_back_to_single_shot_reply() {
+        let tmp = TempDir::new().unwrap();
+        let (env, request) = serve_once(&tmp, "HTTP/1.1 200 OK", "application/json", &[r#"{"response":"Hello World"}"#], true);
+
+        let (reply, deltas) = send_collecting(&env, &ChatRequests::default(), "  Hi  ").await;
+
+        assert_eq!(reply.unwrap(), ChatReply { content: "Hello World".into(), streamed: false, cancelled: false });
+        assert_eq!(deltas, vec!["Hello World"]);
+        let request = request.recv().unwrap();
+        assert!(request.starts_with("POST /webhook HTTP/1.1"), "{}", request);
+        assert!(request.to_ascii_lowercase().contains("accept: text/event-stream"), "{}", request);
+        assert!(request.ends_with(r#"{"message":"Hi","stream":true}"#), "{}", request);
+    }
+
+    #[tokio::test]
+    async fn test_send_streams_event_stream_deltas() {
+        let tmp = TempDir::new().unwrap();
+        let pieces = &["data: {\"delta\":\"Hel\"}\n\n", "data: {\"delta\":\"lo\"}\n\n", "data: [DONE]\n\n"];
+        let (env, _) = serve_once(&tmp, "HTTP/1.1 200 OK", "text/event-stream", pieces, false);
+
+        let (reply, deltas) = send_collecting(&env, &ChatRequests::default(), "Hi").await;
+
+        assert_eq!(reply.unwrap(), ChatReply { content: "Hello".into(), streamed: true, cancelled: false });
+        assert_eq!(deltas, vec!["Hel", "lo"]);
+    }
+
+    #[tokio::test]
+    async fn test_send_stops_when_cancelled() {
+        let tmp = TempDir::new().unwrap();
+        let pieces = &["data: {\"delta\":\"Hel\"}\n\n"];
+        let (env, _) = serve_once(&tmp, "HTTP/1.1 200 OK", "text/event-stream", pieces, false);
+        let requests = ChatRequests::default();
+
+        let cancel_on_first_delta = |_: ChatEvent| {
+            assert!(requests.cancel("r1"));
+        };
+        let reply = tokio::time::timeout(Duration::from_secs(5), send(&env, &requests, "r1", "Hi", &cancel_on_first_delta))
+            .await
+            .expect("cancelled request should return")
+            .unwrap();
+
+        assert_eq!(reply, ChatReply { content: "Hel".into(), streamed: true, cancelled: true });
+        assert!(!requests.cancel("r1"));
+    }
+
+    #[tokio::test]
+    async fn test_send_reports_gateway_errors() {
+        let tmp = TempDir::new().unwrap();
+        let body = &[r#"{"error":"Unauthorized: pairing required"}"#];
+        let (env, _) = serve_once(&tmp, "HTTP/1.1 401 Unauthorized", "application/json", body, true);
+        assert_eq!(
+            send_collecting(&env, &ChatRequests::default(), "Hi").await.0.unwrap_err(),
+            EasyClawError::GatewayRejected { status: None, detail: "Unauthorized: pairing required".into() }
+        );
+
+        let tmp = TempDir::new().unwrap();
+        let (env, _) = serve_once(&tmp, "HTTP/1.1 500 Internal Server Error", "text/plain", &["oops"], true);
+        assert_eq!(
+            send_collecting(&env, &ChatRequests::default(), "Hi").await.0.unwrap_err(),
+            EasyClawError::GatewayRejected { status: Some(500), detail: "Gateway returned HTTP 500".into() }
+        );
+
+        let tmp = TempDir::new().unwrap();
+        let pieces = &["data: partial\n\n", "event: error\ndata: {\"error\":\"provider down\"}\n\n"];
+        let (env, _) = serve_once(&tmp, "HTTP/1.1 200 OK", "text/event-stream", pieces, false);
+        assert_eq!(
+            send_collecting(&env, &ChatRequests::default(), "Hi").await.0.unwrap_err(),
+            EasyClawError::GatewayRejected { status: None, detail: "provider down".into() }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_reports_unreachable_gateway_and_empty_messages() {
+        let tmp = TempDir::new().unwrap();
+        let port = TcpListener::bind(("127.0.0.1", 0)).unwrap().local_addr().unwrap().port();
+        let env = env_for_port(&tmp, port);
+        let requests = ChatRequests::default();
+
+        assert_eq!(send_collecting(&env, &requests, "Hi").await.0.unwrap_err().code(), "gateway_unreachable");
+        assert_eq!(send_collecting(&env, &requests, "   ").await.0.unwrap_err().code(), "invalid_input");
+    }
+}
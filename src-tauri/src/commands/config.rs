@@ -0,0 +1,86 @@
+use log::info;
+use serde_json::Value as JsonValue;
+use tauri::State;
+
+use crate::utils::backup::{self, BackupInfo};
+use crate::utils::config::{self, ZeroClawConfig};
+use crate::utils::context::ZeroClawEnv;
+use crate::utils::error::EasyClawError;
+use crate::utils::validation::Diagnostic;
+
+/// Returns the typed ZeroClaw configuration from ~/.zeroclaw/config.toml.
+#[tauri::command]
+pub async fn get_config(env: State<'_, ZeroClawEnv>) -> Result<ZeroClawConfig, EasyClawError> {
+    let config_path = env.config_path()?;
+    config::load_config(&config_path)
+}
+
+/// Replaces the ZeroClaw configuration. Unchanged values keep their formatting
+/// and comments in config.toml. Invalid configs are refused unless `force` is true.
+#[tauri::command]
+pub async fn update_config(
+    env: State<'_, ZeroClawEnv>,
+    config: ZeroClawConfig,
+    force: Option<bool>,
+) -> Result<(), EasyClawError> {
+    let config_path = env.config_path()?;
+    config::save_config(&config_path, &config, force.unwrap_or(false))?;
+    info!("[update_config] Saved {:?}", config_path);
+    Ok(())
+}
+
+/// Returns a single config value by dotted key path (e.g. `gateway.port`),
+/// or null if the key is not set.
+#[tauri::command]
+pub async fn get_config_value(env: State<'_, ZeroClawEnv>, key: String) -> Result<Option<JsonValue>, EasyClawError> {
+    let config_path = env.config_path()?;
+    config::get_value(&config_path, &key)
+}
+
+/// Sets a single config value by dotted key path. A null value removes the key.
+/// Refused if the result would be invalid, unless `force` is true.
+#[tauri::command]
+pub async fn set_config_value(
+    env: State<'_, ZeroClawEnv>,
+    key: String,
+    value: JsonValue,
+    force: Option<bool>,
+) -> Result<(), EasyClawError> {
+    let config_path = env.config_path()?;
+    if config::set_value(&config_path, &key, &value, force.unwrap_or(false))? {
+        info!("[set_config_value] Updated `{}`", key);
+    }
+    Ok(())
+}
+
+/// Validates config.toml (or the given candidate config, before saving it) and
+/// returns field-level diagnostics for the UI.
+#[tauri::command]
+pub async fn validate_config(
+    env: State<'_, ZeroClawEnv>,
+    config: Option<ZeroClawConfig>,
+) -> Result<Vec<Diagnostic>, EasyClawError> {
+    let config_path = env.config_path()?;
+    config::validate_config(&config_path, config.as_ref())
+}
+
+/// Lists config.toml backups in ~/.zeroclaw/backups, newest first.
+#[tauri::command]
+pub async fn list_config_backups(env: State<'_, ZeroClawEnv>) -> Result<Vec<BackupInfo>, EasyClawError> {
+    backup::list_backups(&env.backups_dir()?)
+}
+
+/// Returns a unified diff from the given backup to the current config.toml.
+#[tauri::command]
+pub async fn diff_config_backup(env: State<'_, ZeroClawEnv>, id: String) -> Result<String, EasyClawError> {
+    backup::diff_backup(&env.backups_dir()?, &id, &env.config_path()?)
+}
+
+/// Restores config.toml from the given backup. The current config is backed up first.
+#[tauri::command]
+pub async fn restore_config_backup(env: State<'_, ZeroClawEnv>, id: String) -> Result<(), EasyClawError> {
+    let config_path = env.config_path()?;
+    backup::restore_backup(&env.backups_dir()?, &id, &config_path, backup::BACKUP_RETENTION)?;
+    info!("[restore_config_backup] Restored config from {}", id);
+    Ok(())
+}
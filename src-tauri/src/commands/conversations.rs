@@ -0,0 +1,88 @@
+use tauri::State;
+
+use crate::utils::context::ZeroClawEnv;
+use crate::utils::conversations::{self, Conversation, ConversationSummary, StoredMessage};
+use crate::utils::error::EasyClawError;
+use crate::utils::export::{self, ExportArchive, ExportFormat, ExportedConversation};
+use crate::utils::search::{HistoryIndex, SearchHit};
+
+/// Hits returned by `search_history` when no limit is given.
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+/// Most hits `search_history` returns.
+const MAX_SEARCH_LIMIT: usize = 100;
+
+/// Lists saved conversations, most recently changed first.
+#[tauri::command]
+pub async fn list_conversations(env: State<'_, ZeroClawEnv>) -> Result<Vec<ConversationSummary>, EasyClawError> {
+    conversations::list_conversations(&env.sessions_dir()?)
+}
+
+/// Returns a saved conversation with all its messages.
+#[tauri::command]
+pub async fn load_conversation(env: State<'_, ZeroClawEnv>, id: String) -> Result<Conversation, EasyClawError> {
+    conversations::load_conversation(&env.sessions_dir()?, &id)
+}
+
+/// Saves a finished message, starting the conversation if `id` is new. Each message
+/// is flushed to disk on its own, so a crash loses at most the one being written.
+#[tauri::command]
+pub async fn append_message(
+    env: State<'_, ZeroClawEnv>,
+    id: String,
+    message: StoredMessage,
+) -> Result<ConversationSummary, EasyClawError> {
+    conversations::append_message(&env.sessions_dir()?, &id, message)
+}
+
+/// Sets a conversation's title.
+#[tauri::command]
+pub async fn rename_conversation(
+    env: State<'_, ZeroClawEnv>,
+    id: String,
+    title: String,
+) -> Result<ConversationSummary, EasyClawError> {
+    conversations::rename_conversation(&env.sessions_dir()?, &id, &title)
+}
+
+/// Deletes a saved conversation.
+#[tauri::command]
+pub async fn delete_conversation(env: State<'_, ZeroClawEnv>, id: String) -> Result<(), EasyClawError> {
+    conversations::delete_conversation(&env.sessions_dir()?, &id)
+}
+
+/// Full-text search over saved conversations and, with `include_memory`, the files in
+/// `workspace/memory`. Returns ranked hits with highlighted snippets.
+#[tauri::command]
+pub async fn search_history(
+    env: State<'_, ZeroClawEnv>,
+    index: State<'_, HistoryIndex>,
+    query: String,
+    limit: Option<usize>,
+    include_memory: Option<bool>,
+) -> Result<Vec<SearchHit>, EasyClawError> {
+    let memory_dir = if include_memory.unwrap_or(false) { Some(env.memory_dir()?) } else { None };
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+    index.search(&env.sessions_dir()?, memory_dir.as_deref(), &query, limit)
+}
+
+/// Renders a saved conversation as Markdown, lossless JSON or a self-contained HTML
+/// page, with the file name to save it under.
+#[tauri::command]
+pub async fn export_conversation(
+    env: State<'_, ZeroClawEnv>,
+    id: String,
+    format: ExportFormat,
+) -> Result<ExportedConversation, EasyClawError> {
+    export::export_conversation(&env.sessions_dir()?, &id, format)
+}
+
+/// Exports all saved conversations in `format` into a zip archive in the exports
+/// directory, named `file_name` (a plain `.zip` name) or after the current time.
+#[tauri::command]
+pub async fn export_all_conversations(
+    env: State<'_, ZeroClawEnv>,
+    format: ExportFormat,
+    file_name: Option<String>,
+) -> Result<ExportArchive, EasyClawError> {
+    export::export_all(&env.sessions_dir()?, format, &env.exports_dir()?, file_name.as_deref())
+}
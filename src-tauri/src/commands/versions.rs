@@ -0,0 +1,105 @@
+use std::sync::Mutex;
+
+use log::{error, info};
+use tauri::{AppHandle, State};
+
+use crate::commands::gateway;
+use crate::utils::context::ZeroClawEnv;
+use crate::utils::embedded::EmbeddedGateway;
+use crate::utils::error::EasyClawError;
+use crate::utils::installer::{InstalledVersion, VersionStore};
+use crate::utils::supervisor::{SupervisedState, Supervisor};
+
+/// Lists the ZeroClaw versions kept under ~/.zeroclaw/bin/versions/, newest first.
+#[tauri::command]
+pub async fn list_zeroclaw_versions(env: State<'_, ZeroClawEnv>) -> Result<Vec<InstalledVersion>, EasyClawError> {
+    VersionStore::for_binary(&env.bin_path()?).list()
+}
+
+/// Switches ~/.zeroclaw/bin/zeroclaw to an installed version (e.g. to roll back a bad
+/// upgrade). A running gateway is stopped first, since its binary can't be replaced while
+/// it runs on Windows, and started again with the activated version.
+#[tauri::command]
+pub async fn activate_zeroclaw_version(
+    app: AppHandle,
+    env: State<'_, ZeroClawEnv>,
+    embedded: State<'_, EmbeddedGateway>,
+    supervisor: State<'_, Mutex<Supervisor>>,
+    version: String,
+) -> Result<String, EasyClawError> {
+    activate(&env, &embedded, &supervisor, Some(&app), &version).await
+}
+
+/// Activates `version` around a gateway stop and start, see `activate_zeroclaw_version`.
+pub async fn activate(
+    env: &ZeroClawEnv,
+    embedded: &EmbeddedGateway,
+    supervisor: &Mutex<Supervisor>,
+    app: Option<&AppHandle>,
+    version: &str,
+) -> Result<String, EasyClawError> {
+    let store = VersionStore::for_binary(&env.bin_path()?);
+    let running = gateway::lock_supervisor(supervisor).state() != SupervisedState::Idle;
+    if running {
+        gateway::stop(env, embedded, supervisor, app).await?;
+    }
+
+    if let Err(e) = store.activate(version) {
+        if running {
+            // Bring the previous version back up
+            if let Err(start_err) = gateway::start(env, embedded, supervisor, app).await {
+                error!("[activate_zeroclaw_version] Gateway start failed: {}", start_err);
+            }
+        }
+        return Err(e);
+    }
+    info!("[activate_zeroclaw_version] Activated ZeroClaw {}", version);
+
+    if !running {
+        return Ok(format!("ZeroClaw {} activated", version));
+    }
+
+    gateway::start(env, embedded, supervisor, app).await.map_err(|e| {
+        error!("[activate_zeroclaw_version] Gateway start failed: {}", e);
+        e.with_context(format!("ZeroClaw {} activated, but starting the gateway failed", version))
+    })?;
+    Ok(format!("ZeroClaw {} activated and gateway restarted", version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::platform;
+    use crate::utils::process::{ProcessOutput, ScriptedRunner};
+    use std::fs;
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_activate_stops_gateway_before_replacing_binary() {
+        let tmp = TempDir::new().unwrap();
+        let bin_path = tmp.path().join("bin").join(platform::BINARY_NAME);
+        let running_bin = bin_path.clone();
+        let runner = Arc::new(ScriptedRunner::new(move |args| {
+            let expected = if args == ["service", "stop"] { "0.1.0" } else { "0.2.0" };
+            assert_eq!(fs::read_to_string(&running_bin).unwrap(), expected, "zeroclaw {:?}", args);
+            Ok(ProcessOutput { code: Some(0), ..ProcessOutput::default() })
+        }));
+        let env = ZeroClawEnv::with_root(tmp.path()).with_runner(runner.clone());
+        let stored = bin_path.parent().unwrap().join("versions").join("0.2.0").join(platform::BINARY_NAME);
+        fs::create_dir_all(stored.parent().unwrap()).unwrap();
+        fs::write(&stored, "0.2.0").unwrap();
+        fs::write(&bin_path, "0.1.0").unwrap();
+        let free = TcpListener::bind(("127.0.0.1", 0)).unwrap().local_addr().unwrap().port();
+        fs::write(tmp.path().join("config.toml"), format!("[gateway]\nport = {}\n", free)).unwrap();
+        fs::write(env.settings_path().unwrap(), r#"{"gateway_mode":"service"}"#).unwrap();
+        let supervisor = Mutex::new(Supervisor::default());
+        gateway::lock_supervisor(&supervisor).enable();
+
+        let message = activate(&env, &EmbeddedGateway::default(), &supervisor, None, "0.2.0").await.unwrap();
+
+        assert_eq!(message, "ZeroClaw 0.2.0 activated and gateway restarted");
+        assert_eq!(runner.calls(), vec!["service stop", "service status", "service install", "service start"]);
+    }
+}
@@ -1,19 +1,81 @@
 pub mod commands;
 pub mod utils;
 
+use commands::chat;
+use commands::config;
+use commands::conversations;
 use commands::gateway;
 use commands::init;
+use commands::profiles;
+use commands::settings;
+use commands::versions;
+use tauri::{Manager, RunEvent};
+use std::sync::Mutex;
+use utils::chat_stream::ChatRequests;
+use utils::context::ZeroClawEnv;
+use utils::embedded::EmbeddedGateway;
+use utils::search::HistoryIndex;
+use utils::supervisor::Supervisor;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_log::Builder::default().build())
+        .manage(EmbeddedGateway::default())
+        .manage(Mutex::new(Supervisor::default()))
+        .manage(ChatRequests::default())
+        .manage(HistoryIndex::default())
+        .setup(|app| {
+            app.manage(ZeroClawEnv::system(app.path().resource_dir().ok()));
+            tauri::async_runtime::spawn(gateway::supervise_gateway(app.handle().clone()));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             init::check_initialized,
             init::initialize_zeroclaw,
+            init::sync_zeroclaw_binary,
+            versions::list_zeroclaw_versions,
+            versions::activate_zeroclaw_version,
             gateway::gateway_status,
             gateway::start_gateway,
+            gateway::stop_gateway,
+            gateway::restart_gateway,
+            gateway::uninstall_gateway_service,
+            gateway::get_gateway_endpoint,
+            chat::send_chat_message,
+            chat::cancel_chat_message,
+            conversations::list_conversations,
+            conversations::load_conversation,
+            conversations::append_message,
+            conversations::rename_conversation,
+            conversations::delete_conversation,
+            conversations::search_history,
+            conversations::export_conversation,
+            conversations::export_all_conversations,
+            config::get_config,
+            config::update_config,
+            config::get_config_value,
+            config::set_config_value,
+            config::validate_config,
+            config::list_config_backups,
+            config::diff_config_backup,
+            config::restore_config_backup,
+            settings::get_app_settings,
+            settings::update_app_settings,
+            settings::get_zeroclaw_home,
+            profiles::list_profiles,
+            profiles::create_profile,
+            profiles::switch_profile,
+            profiles::delete_profile,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Never leave an embedded gateway running after EasyClaw quits
+            if let RunEvent::Exit = event {
+                if let Err(e) = app.state::<EmbeddedGateway>().stop() {
+                    log::warn!("[run] Failed to stop embedded gateway: {}", e);
+                }
+            }
+        });
 }
@@ -0,0 +1,256 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use log::info;
+use serde::Serialize;
+use similar::TextDiff;
+
+use crate::utils::error::EasyClawError;
+use crate::utils::fs::{write_atomic, PRIVATE_FILE_MODE};
+
+/// Maximum number of config backups kept; older ones are pruned.
+pub const BACKUP_RETENTION: usize = 20;
+
+const BACKUP_PREFIX: &str = "config-";
+const BACKUP_SUFFIX: &str = ".toml";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+
+/// A timestamped copy of config.toml.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BackupInfo {
+    /// File name of the backup, used as its identifier.
+    pub id: String,
+    /// Creation time in RFC 3339 (UTC).
+    pub created_at: String,
+    pub size: u64,
+}
+
+/// Copies `config_path` into `backups_dir` under a timestamped name and prunes old backups
+/// beyond `retention`. Nothing is written if the file doesn't exist or is identical to the
+/// most recent backup. Returns the new backup, if one was created.
+pub fn create_backup(
+    config_path: &Path,
+    backups_dir: &Path,
+    retention: usize,
+) -> Result<Option<BackupInfo>, EasyClawError> {
+    if !config_path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read(config_path)
+        .map_err(|e| EasyClawError::io(format!("Failed to read {}", config_path.display()), &e))?;
+
+    let existing = list_backups(backups_dir)?;
+    if let Some(latest) = existing.first() {
+        if fs::read(backups_dir.join(&latest.id)).ok().as_deref() == Some(content.as_slice()) {
+            return Ok(None);
+        }
+    }
+
+    fs::create_dir_all(backups_dir)
+        .map_err(|e| EasyClawError::io(format!("Failed to create backup dir {}", backups_dir.display()), &e))?;
+
+    // Never go backwards or collide with the latest backup, so ids stay unique and sortable
+    let mut timestamp = Utc::now().naive_utc();
+    if let Some(latest) = existing.first().and_then(|b| parse_timestamp(&b.id)) {
+        timestamp = timestamp.max(latest + Duration::milliseconds(1));
+    }
+    let backup_path = backups_dir.join(backup_file_name(&timestamp));
+
+    write_atomic(&backup_path, &content, Some(PRIVATE_FILE_MODE))?;
+    info!("[create_backup] Backed up {:?} to {:?}", config_path, backup_path);
+
+    prune_backups(backups_dir, retention)?;
+
+    Ok(backup_info(&backup_path))
+}
+
+/// Lists backups in `backups_dir`, newest first. A missing directory yields an empty list.
+pub fn list_backups(backups_dir: &Path) -> Result<Vec<BackupInfo>, EasyClawError> {
+    if !backups_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(backups_dir)
+        .map_err(|e| EasyClawError::io(format!("Failed to read backup dir {}", backups_dir.display()), &e))?;
+
+    let mut backups: Vec<BackupInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| backup_info(&entry.path()))
+        .collect();
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(backups)
+}
+
+/// Returns a unified diff from the backup `id` to the current `config_path`.
+/// An empty string means the two are identical.
+pub fn diff_backup(backups_dir: &Path, id: &str, config_path: &Path) -> Result<String, EasyClawError> {
+    let backup = fs::read_to_string(resolve_backup(backups_dir, id)?)
+        .map_err(|e| EasyClawError::io(format!("Failed to read backup {}", id), &e))?;
+    let current = if config_path.exists() {
+        fs::read_to_string(config_path)
+            .map_err(|e| EasyClawError::io(format!("Failed to read {}", config_path.display()), &e))?
+    } else {
+        String::new()
+    };
+
+    if backup == current {
+        return Ok(String::new());
+    }
+
+    Ok(TextDiff::from_lines(&backup, &current)
+        .unified_diff()
+        .header(id, "config.toml")
+        .to_string())
+}
+
+/// Restores backup `id` over `config_path`. The current file is backed up first
+/// so the restore itself can be undone.
+pub fn restore_backup(
+    backups_dir: &Path,
+    id: &str,
+    config_path: &Path,
+    retention: usize,
+) -> Result<(), EasyClawError> {
+    let backup_path = resolve_backup(backups_dir, id)?;
+    let content = fs::read(&backup_path)
+        .map_err(|e| EasyClawError::io(format!("Failed to read backup {}", id), &e))?;
+
+    create_backup(config_path, backups_dir, retention)?;
+
+    write_atomic(config_path, &content, Some(PRIVATE_FILE_MODE))?;
+    info!("[restore_backup] Restored {:?} from {}", config_path, id);
+    Ok(())
+}
+
+/// Deletes the oldest backups so that at most `retention` remain.
+fn prune_backups(backups_dir: &Path, retention: usize) -> Result<(), EasyClawError> {
+    for stale in list_backups(backups_dir)?.iter().skip(retention) {
+        let path = backups_dir.join(&stale.id);
+        fs::remove_file(&path)
+            .map_err(|e| EasyClawError::io(format!("Failed to remove old backup {}", path.display()), &e))?;
+    }
+    Ok(())
+}
+
+/// Maps a backup id to its path, rejecting anything that isn't one of our backup files.
+fn resolve_backup(backups_dir: &Path, id: &str) -> Result<PathBuf, EasyClawError> {
+    let path = backups_dir.join(id);
+    if parse_timestamp(id).is_none() || !path.is_file() {
+        return Err(EasyClawError::NotFound(format!("Backup not found: {}", id)));
+    }
+    Ok(path)
+}
+
+fn backup_file_name(timestamp: &NaiveDateTime) -> String {
+    format!("{}{}{}", BACKUP_PREFIX, timestamp.format(TIMESTAMP_FORMAT), BACKUP_SUFFIX)
+}
+
+fn parse_timestamp(file_name: &str) -> Option<NaiveDateTime> {
+    let stamp = file_name.strip_prefix(BACKUP_PREFIX)?.strip_suffix(BACKUP_SUFFIX)?;
+    NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT).ok()
+}
+
+fn backup_info(path: &Path) -> Option<BackupInfo> {
+    let id = path.file_name()?.to_str()?.to_string();
+    let timestamp = parse_timestamp(&id)?;
+    let size = fs::metadata(path).ok()?.len();
+    Some(BackupInfo {
+        id,
+        created_at: timestamp.and_utc().to_rfc3339(),
+        size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, PathBuf, PathBuf) {
+        let tmp = TempDir::new().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        let backups_dir = tmp.path().join("backups");
+        (tmp, config_path, backups_dir)
+    }
+
+    #[test]
+    fn test_create_backup_copies_file_with_timestamped_name() {
+        let (_tmp, config_path, backups_dir) = setup();
+        fs::write(&config_path, "port = 1\n").unwrap();
+
+        let backup = create_backup(&config_path, &backups_dir, BACKUP_RETENTION).unwrap().unwrap();
+
+        assert!(backup.id.starts_with("config-") && backup.id.ends_with(".toml"));
+        assert_eq!(backup.size, 9);
+        assert_eq!(fs::read_to_string(backups_dir.join(&backup.id)).unwrap(), "port = 1\n");
+        assert_eq!(list_backups(&backups_dir).unwrap(), vec![backup]);
+    }
+
+    #[test]
+    fn test_create_backup_skips_missing_file_and_unchanged_content() {
+        let (_tmp, config_path, backups_dir) = setup();
+
+        assert!(create_backup(&config_path, &backups_dir, BACKUP_RETENTION).unwrap().is_none());
+
+        fs::write(&config_path, "port = 1\n").unwrap();
+        assert!(create_backup(&config_path, &backups_dir, BACKUP_RETENTION).unwrap().is_some());
+        assert!(create_backup(&config_path, &backups_dir, BACKUP_RETENTION).unwrap().is_none());
+        assert_eq!(list_backups(&backups_dir).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_retention_keeps_newest_backups() {
+        let (_tmp, config_path, backups_dir) = setup();
+
+        for i in 0..5 {
+            fs::write(&config_path, format!("port = {}\n", i)).unwrap();
+            create_backup(&config_path, &backups_dir, 3).unwrap();
+        }
+
+        let backups = list_backups(&backups_dir).unwrap();
+        assert_eq!(backups.len(), 3);
+        assert_eq!(fs::read_to_string(backups_dir.join(&backups[0].id)).unwrap(), "port = 4\n");
+        assert_eq!(fs::read_to_string(backups_dir.join(&backups[2].id)).unwrap(), "port = 2\n");
+    }
+
+    #[test]
+    fn test_diff_backup_against_current() {
+        let (_tmp, config_path, backups_dir) = setup();
+        fs::write(&config_path, "[gateway]\nport = 3000\n").unwrap();
+        let backup = create_backup(&config_path, &backups_dir, BACKUP_RETENTION).unwrap().unwrap();
+
+        assert_eq!(diff_backup(&backups_dir, &backup.id, &config_path).unwrap(), "");
+
+        fs::write(&config_path, "[gateway]\nport = 18789\n").unwrap();
+        let diff = diff_backup(&backups_dir, &backup.id, &config_path).unwrap();
+        assert!(diff.contains("-port = 3000"));
+        assert!(diff.contains("+port = 18789"));
+    }
+
+    #[test]
+    fn test_restore_backup_backs_up_current_first() {
+        let (_tmp, config_path, backups_dir) = setup();
+        fs::write(&config_path, "good = true\n").unwrap();
+        let good = create_backup(&config_path, &backups_dir, BACKUP_RETENTION).unwrap().unwrap();
+        fs::write(&config_path, "broken = \n").unwrap();
+
+        restore_backup(&backups_dir, &good.id, &config_path, BACKUP_RETENTION).unwrap();
+
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), "good = true\n");
+        let backups = list_backups(&backups_dir).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(fs::read_to_string(backups_dir.join(&backups[0].id)).unwrap(), "broken = \n");
+    }
+
+    #[test]
+    fn test_unknown_or_malicious_ids_are_rejected() {
+        let (_tmp, config_path, backups_dir) = setup();
+        fs::write(&config_path, "x = 1\n").unwrap();
+
+        let err = diff_backup(&backups_dir, "config-20260101-000000-000.toml", &config_path).unwrap_err();
+        assert_eq!(err.code(), "not_found");
+        assert!(restore_backup(&backups_dir, "../config.toml", &config_path, BACKUP_RETENTION).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value as JsonValue;
+use tokio::sync::watch;
+
+use crate::utils::error::EasyClawError;
+
+/// Fields of a streamed event that may hold the next piece of the reply, in order of preference.
+const DELTA_FIELDS: [&str; 6] = ["delta", "token", "text", "content", "reply", "response"];
+
+/// How the gateway streams a reply, picked from the response's `Content-Type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// `text/event-stream`: SSE events whose `data` carries a delta.
+    EventStream,
+    /// `application/x-ndjson` and similar: one JSON object per line.
+    JsonLines,
+}
+
+impl StreamFormat {
+    /// The streaming format for a `Content-Type`, or None for a single-shot reply.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        match mime.as_str() {
+            "text/event-stream" => Some(StreamFormat::EventStream),
+            "application/x-ndjson" | "application/jsonl" | "application/jsonlines" => Some(StreamFormat::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+/// One decoded piece of a streamed reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamItem {
+    /// The next piece of the reply text.
+    Delta(String),
+    /// The gateway reported an error mid-stream.
+    Error(String),
+    /// The gateway marked the reply as complete.
+    Done,
+}
+
+/// Splits the chunks of a streamed reply into lines and decodes them. Chunks can end
+/// anywhere, even inside a UTF-8 character; only complete lines are decoded.
+#[derive(Debug)]
+pub struct StreamDecoder {
+    format: StreamFormat,
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl StreamDecoder {
+    pub fn new(format: StreamFormat) -> Self {
+        Self { format, buffer: Vec::new(), event: None, data: Vec::new() }
+    }
+
+    /// Decodes the complete lines received so far.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<StreamItem> {
+        self.buffer.extend_from_slice(chunk);
+        let mut items = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            self.decode_line(line.trim_end_matches(['\r', '\n']), &mut items);
+        }
+        items
+    }
+
+    /// Decodes whatever is left once the response has ended.
+    pub fn finish(&mut self) -> Vec<StreamItem> {
+        let mut items = Vec::new();
+        if !self.buffer.is_empty() {
+            let rest = std::mem::take(&mut self.buffer);
+            self.decode_line(String::from_utf8_lossy(&rest).trim_end_matches('\r'), &mut items);
+        }
+        if self.format == StreamFormat::EventStream {
+            self.dispatch_event(&mut items);
+        }
+        items
+    }
+
+    fn decode_line(&mut self, line: &str, items: &mut Vec<StreamItem>) {
+        match self.format {
+            StreamFormat::JsonLines if !line.trim().is_empty() => items.extend(decode_json_line(line)),
+            StreamFormat::JsonLines => {}
+            StreamFormat::EventStream if line.is_empty() => self.dispatch_event(items),
+            // Comment, e.g. a keep-alive
+            StreamFormat::EventStream if line.starts_with(':') => {}
+            StreamFormat::EventStream => {
+                let (field, value) = line.split_once(':').unwrap_or((line, ""));
+                let value = value.strip_prefix(' ').unwrap_or(value);
+                match field {
+                    "event" => self.event = Some(value.to_string()),
+                    "data" => self.data.push(value.to_string()),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn dispatch_event(&mut self, items: &mut Vec<StreamItem>) {
+        let event = self.event.take();
+        if self.data.is_empty() {
+            return;
+        }
+        let data = std::mem::take(&mut self.data).join("\n");
+        match event.as_deref() {
+            Some("error") => items.push(StreamItem::Error(error_text(&data))),
+            Some("done") => items.push(StreamItem::Done),
+            _ if data == "[DONE]" => items.push(StreamItem::Done),
+            _ => items.extend(delta_text(&data).map(StreamItem::Delta)),
+        }
+    }
+}
+
+fn decode_json_line(line: &str) -> Option<StreamItem> {
+    let Ok(value) = serde_json::from_str::<JsonValue>(line) else {
+        return Some(StreamItem::Delta(line.to_string()));
+    };
+    if let Some(error) = value.get("error").and_then(JsonValue::as_str) {
+        return Some(StreamItem::Error(error.to_string()));
+    }
+    if let Some(text) = json_delta(&value) {
+        return Some(StreamItem::Delta(text));
+    }
+    (value.get("done").and_then(JsonValue::as_bool) == Some(true)).then_some(StreamItem::Done)
+}
+
+/// The reply text in an SSE `data` payload: a field of a JSON object, a JSON string
+/// or, if it isn't JSON, the payload itself.
+fn delta_text(data: &str) -> Option<String> {
+    match serde_json::from_str::<JsonValue>(data) {
+        Ok(value) => json_delta(&value),
+        Err(_) => Some(data.to_string()),
+    }
+}
+
+/// Looks for the delta in the usual fields, including OpenAI-style `choices[0].delta.content`.
+fn json_delta(value: &JsonValue) -> Option<String> {
+    if let JsonValue::String(text) = value {
+        return Some(text.clone());
+    }
+    DELTA_FIELDS
+        .iter()
+        .find_map(|field| value.get(field).and_then(JsonValue::as_str))
+        .or_else(|| value.pointer("/choices/0/delta/content").and_then(JsonValue::as_str))
+        .map(str::to_string)
+}
+
+fn error_text(data: &str) -> String {
+    serde_json::from_str::<JsonValue>(data)
+        .ok()
+        .and_then(|value| {
+            ["error", "message"]
+                .iter()
+                .find_map(|field| value.get(field).and_then(JsonValue::as_str).map(str::to_string))
+        })
+        .unwrap_or_else(|| data.to_string())
+}
+
+/// Chat requests in flight, by the id the frontend gave them, so a request can be
+/// cancelled while its reply streams. Held in Tauri managed state.
+#[derive(Debug, Default)]
+pub struct ChatRequests {
+    active: Arc<Mutex<HashMap<String, watch::Sender<bool>>>>,
+}
+
+impl ChatRequests {
+    /// Registers a request; it stays cancellable until the returned guard is dropped.
+    pub fn register(&self, request_id: &str) -> Result<ChatRequestGuard, EasyClawError> {
+        let mut active = lock(&self.active);
+        if active.contains_key(request_id) {
+            return Err(EasyClawError::InvalidInput(format!("Chat request '{}' is already running", request_id)));
+        }
+        let (sender, cancelled) = watch::channel(false);
+        active.insert(request_id.to_string(), sender);
+        Ok(ChatRequestGuard { id: request_id.to_string(), active: Arc::clone(&self.active), cancelled })
+    }
+
+    /// Cancels a running request. Returns false if no request has this id.
+    pub fn cancel(&self, request_id: &str) -> bool {
+        match lock(&self.active).get(request_id) {
+            Some(sender) => {
+                sender.send_replace(true);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A registered chat request; unregisters it when dropped.
+#[derive(Debug)]
+pub struct ChatRequestGuard {
+    id: String,
+    active: Arc<Mutex<HashMap<String, watch::Sender<bool>>>>,
+    cancelled: watch::Receiver<bool>,
+}
+
+impl ChatRequestGuard {
+    /// Resolves once the request is cancelled.
+    pub async fn cancelled(&mut self) {
+        // The sender lives in `active` until this guard is dropped, so this can't fail
+        let _ = self.cancelled.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl Drop for ChatRequestGuard {
+    fn drop(&mut self) {
+        lock(&self.active).remove(&self.id);
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn decode_all(format: StreamFormat, chunks: &[&[u8]]) -> Vec<StreamItem> {
+        let mut decoder = StreamDecoder::new(format);
+        let mut items: Vec<StreamItem> = chunks.iter().flat_map(|chunk| decoder.push(chunk)).collect();
+        items.extend(decoder.finish());
+        items
+    }
+
+    #[test]
+    fn test_event_stream_split_across_chunks() {
+        let stream = "data: {\"delta\":\"你好\"}\r\n\r\n: keep-alive\n\ndata: {\"choices\":[{\"delta\":{\"content\":\", world\"}}]}\n\ndata: [DONE]\n\n";
+        let bytes = stream.as_bytes();
+        // Split inside the first multi-byte character
+        let items = decode_all(StreamFormat::EventStream, &[&bytes[..17], &bytes[17..40], &bytes[40..]]);
+
+        assert_eq!(
+            items,
+            vec![StreamItem::Delta("你好".into()), StreamItem::Delta(", world".into()), StreamItem::Done]
+        );
+    }
+
+    #[test]
+    fn test_event_stream_plain_text_and_errors() {
+        let items = decode_all(
+            StreamFormat::EventStream,
+            &[b"data: line one\ndata: line two\n\nevent: error\ndata: {\"error\":\"rate limited\"}\n\ndata: tail"],
+        );
+
+        assert_eq!(
+            items,
+            vec![
+                StreamItem::Delta("line one\nline two".into()),
+                StreamItem::Error("rate limited".into()),
+                StreamItem::Delta("tail".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let items = decode_all(
+            StreamFormat::JsonLines,
+            &[b"{\"token\":\"a\"}\n\n{\"token\":", b"\"b\"}\n{\"done\":true}"],
+        );
+
+        assert_eq!(items, vec![StreamItem::Delta("a".into()), StreamItem::Delta("b".into()), StreamItem::Done]);
+        assert_eq!(StreamFormat::from_content_type("text/event-stream; charset=utf-8"), Some(StreamFormat::EventStream));
+        assert_eq!(StreamFormat::from_content_type("application/json"), None);
+    }
+
+    #[tokio::test]
+    async fn test_chat_requests_cancel_and_unregister() {
+        let requests = ChatRequests::default();
+        let mut guard = requests.register("r1").unwrap();
+        assert_eq!(requests.register("r1").unwrap_err().code(), "invalid_input");
+
+        assert!(requests.cancel("r1"));
+        tokio::time::timeout(Duration::from_secs(1), guard.cancelled()).await.unwrap();
+
+        drop(guard);
+        assert!(!requests.cancel("r1"));
+        assert!(requests.register("r1").is_ok());
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::utils::error::EasyClawError;
+
+/// `(path relative to resources/bin, sha256, size)` of every bundled binary, generated by build.rs.
+const BUNDLED_MANIFEST: &[(&str, &str, u64)] = include!(concat!(env!("OUT_DIR"), "/binary_manifest.rs"));
+
+/// Expected SHA-256 (lowercase hex) and size of a binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryDigest {
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl BinaryDigest {
+    /// Hashes the file at `path`.
+    pub fn of_file(path: &Path) -> Result<Self, EasyClawError> {
+        let mut file = File::open(path).map_err(|e| EasyClawError::io(format!("Failed to open {}", path.display()), &e))?;
+        let mut hasher = Sha256::new();
+        let size = io::copy(&mut file, &mut hasher)
+            .map_err(|e| EasyClawError::io(format!("Failed to read {}", path.display()), &e))?;
+        let sha256 = hasher.finalize().iter().fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{:02x}", byte);
+            hex
+        });
+        Ok(Self { sha256, size })
+    }
+
+    /// Checks that the file at `path` matches this digest. The size is compared first so a
+    /// truncated file is reported as such.
+    pub fn verify(&self, path: &Path) -> Result<(), EasyClawError> {
+        let actual = Self::of_file(path)?;
+        if actual.size != self.size {
+            return Err(EasyClawError::IntegrityCheckFailed(format!(
+                "{} failed integrity check: expected {} bytes, found {} (truncated or corrupted)",
+                path.display(),
+                self.size,
+                actual.size
+            )));
+        }
+        if actual.sha256 != self.sha256 {
+            return Err(EasyClawError::IntegrityCheckFailed(format!(
+                "{} failed integrity check: SHA-256 is {}, expected {} (modified or corrupted)",
+                path.display(),
+                actual.sha256,
+                self.sha256
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Digest recorded at build time for the bundled binary `name` (relative to `resources/bin`).
+pub fn bundled_digest(name: &str) -> Result<BinaryDigest, EasyClawError> {
+    BUNDLED_MANIFEST
+        .iter()
+        .find(|(entry, _, _)| *entry == name)
+        .map(|(_, sha256, size)| BinaryDigest { sha256: sha256.to_string(), size: *size })
+        .ok_or_else(|| {
+            EasyClawError::IntegrityCheckFailed(format!(
+                "No checksum for bundled binary '{}': it was not in resources/bin when the app was built",
+                name
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_of_file_hashes_contents() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("bin");
+        fs::write(&path, "abc").unwrap();
+
+        let digest = BinaryDigest::of_file(&path).unwrap();
+
+        assert_eq!(digest.size, 3);
+        assert_eq!(digest.sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    #[test]
+    fn test_verify_detects_truncation_and_tampering() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("bin");
+        fs::write(&path, "original binary").unwrap();
+        let digest = BinaryDigest::of_file(&path).unwrap();
+        assert!(digest.verify(&path).is_ok());
+
+        fs::write(&path, "original").unwrap();
+        let err = digest.verify(&path).unwrap_err();
+        assert_eq!(err.code(), "integrity_check_failed");
+        assert!(err.detail().contains("truncated"));
+
+        fs::write(&path, "tampered binary").unwrap();
+        assert!(digest.verify(&path).unwrap_err().detail().contains("SHA-256"));
+    }
+
+    #[test]
+    fn test_bundled_digest_unknown_binary() {
+        assert!(bundled_digest("no-such-binary").unwrap_err().detail().contains("no-such-binary"));
+    }
+}
@@ -0,0 +1,496 @@
+use std::fs;
+use std::path::Path;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use toml_edit::{Array, InlineTable, Item, Value};
+
+use crate::utils::backup;
+use crate::utils::error::EasyClawError;
+use crate::utils::fs::PRIVATE_FILE_MODE;
+use crate::utils::toml_doc::{split_key_path, TomlDocument};
+use crate::utils::validation::{self, Diagnostic};
+
+/// Typed view of ZeroClaw's `config.toml`.
+///
+/// Only the settings EasyClaw knows about are modelled explicitly; every other key
+/// is kept in `extra` so that a read-modify-write round trip never drops data.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZeroClawConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_temperature: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<GatewayConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autonomy: Option<AutonomyConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channels_config: Option<ChannelsConfig>,
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+/// `[gateway]` section: the local HTTP gateway EasyClaw talks to.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GatewayConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_pairing: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_public_bind: Option<bool>,
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+/// `[memory]` section.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_save: Option<bool>,
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+/// `[autonomy]` section.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutonomyConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_only: Option<bool>,
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+/// `[channels_config]` section. Individual channels (telegram, discord, ...) are
+/// passed through untouched in `extra`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelsConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cli: Option<bool>,
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+/// Reads and deserializes config.toml into the typed model.
+pub fn load_config(config_path: &Path) -> Result<ZeroClawConfig, EasyClawError> {
+    let content = read_config(config_path)?;
+    toml::from_str(&content).map_err(|e| parse_error(config_path, e))
+}
+
+/// Writes the typed model back to config.toml.
+///
+/// The existing file is edited in place: unchanged values keep their formatting and
+/// comments, and keys that are no longer present in `config` are removed.
+/// The result is validated first and not written if it has errors, unless `force` is set.
+pub fn save_config(config_path: &Path, config: &ZeroClawConfig, force: bool) -> Result<(), EasyClawError> {
+    let value = serde_json::to_value(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    let mut doc = load_document(config_path)?;
+    if apply_json(&mut doc, "", &value).map_err(EasyClawError::InvalidInput)? {
+        save_validated(&doc, config_path, force)?;
+    }
+    Ok(())
+}
+
+/// Returns the value at a dotted key path (e.g. `gateway.port`) as JSON,
+/// or None if the key is not set.
+pub fn get_value(config_path: &Path, key_path: &str) -> Result<Option<JsonValue>, EasyClawError> {
+    let segments = split_key_path(key_path).map_err(EasyClawError::InvalidInput)?;
+    let content = read_config(config_path)?;
+    let table: toml::Table = toml::from_str(&content).map_err(|e| parse_error(config_path, e))?;
+
+    let mut segments = segments.into_iter();
+    let mut current = segments.next().and_then(|s| table.get(s));
+    for segment in segments {
+        current = current.and_then(|v| v.get(segment));
+    }
+
+    current
+        .map(|v| {
+            serde_json::to_value(v)
+                .map_err(|e| EasyClawError::Internal(format!("Failed to convert `{}`: {}", key_path, e)))
+        })
+        .transpose()
+}
+
+/// Sets the value at a dotted key path. Objects replace the whole table at that path,
+/// `null` removes the key. Returns true if the file changed.
+/// Like `save_config`, refuses to write an invalid result unless `force` is set.
+pub fn set_value(config_path: &Path, key_path: &str, value: &JsonValue, force: bool) -> Result<bool, EasyClawError> {
+    let mut doc = load_document(config_path)?;
+    let changed = apply_json(&mut doc, key_path, value).map_err(EasyClawError::InvalidInput)?;
+    if changed {
+        save_validated(&doc, config_path, force)?;
+    }
+    Ok(changed)
+}
+
+/// Validates config.toml on disk, or a candidate config if one is given.
+pub fn validate_config(
+    config_path: &Path,
+    candidate: Option<&ZeroClawConfig>,
+) -> Result<Vec<Diagnostic>, EasyClawError> {
+    match candidate {
+        Some(config) => {
+            let table = toml::Table::try_from(config)
+                .map_err(|e| format!("Failed to serialize config: {}", e))?;
+            Ok(validation::validate(&table))
+        }
+        None => Ok(validation::validate_str(&read_config(config_path)?)),
+    }
+}
+
+fn read_config(config_path: &Path) -> Result<String, EasyClawError> {
+    fs::read_to_string(config_path)
+        .map_err(|e| EasyClawError::io(format!("Failed to read {}", config_path.display()), &e))
+}
+
+fn parse_error(config_path: &Path, e: impl std::fmt::Display) -> EasyClawError {
+    EasyClawError::ConfigInvalid(format!("Failed to parse {}: {}", config_path.display(), e))
+}
+
+fn save_validated(doc: &TomlDocument, config_path: &Path, force: bool) -> Result<(), EasyClawError> {
+    let diagnostics = validation::validate_str(&doc.to_string());
+    if validation::has_errors(&diagnostics) {
+        if !force {
+            return Err(EasyClawError::ConfigInvalid(validation::describe_errors(&diagnostics)));
+        }
+        warn!("[save_config] Forcing save of invalid config: {}", validation::describe_errors(&diagnostics));
+    }
+    write_config(config_path, doc)
+}
+
+/// Writes config.toml, first backing up the current file into the sibling
+/// `backups/` directory (i.e. ~/.zeroclaw/backups). All config writes go through here;
+/// the write is atomic and the file is kept private (chmod 600).
+pub fn write_config(config_path: &Path, doc: &TomlDocument) -> Result<(), EasyClawError> {
+    let backups_dir = config_path.with_file_name("backups");
+    backup::create_backup(config_path, &backups_dir, backup::BACKUP_RETENTION)?;
+    doc.save(config_path, Some(PRIVATE_FILE_MODE))
+}
+
+/// Loads config.toml for editing, starting from an empty document if it doesn't exist yet.
+fn load_document(config_path: &Path) -> Result<TomlDocument, EasyClawError> {
+    if config_path.exists() {
+        TomlDocument::parse(&read_config(config_path)?).map_err(|e| parse_error(config_path, e))
+    } else {
+        Ok(TomlDocument::default())
+    }
+}
+
+/// Applies a JSON value at `key_path` (the document root for `""`).
+/// Objects are synced key by key so untouched entries keep their formatting.
+fn apply_json(doc: &mut TomlDocument, key_path: &str, value: &JsonValue) -> Result<bool, String> {
+    match value {
+        JsonValue::Null => doc.remove(key_path),
+        JsonValue::Object(map) => {
+            let mut changed = false;
+            for existing in doc.table_keys(key_path) {
+                if !map.contains_key(&existing) {
+                    changed |= doc.remove(&join_key(key_path, &existing))?;
+                }
+            }
+            if !key_path.is_empty() && !matches!(doc.get(key_path), Some(item) if item.is_table_like()) {
+                // Replace a scalar with a table, which stays even if it ends up empty
+                changed |= doc.remove(key_path)?;
+                changed |= doc.insert_table(key_path)?;
+            }
+            for (key, child) in map {
+                changed |= apply_json(doc, &join_key(key_path, key), child)?;
+            }
+            Ok(changed)
+        }
+        // Keep `[[...]]` sections as they are, editing each table in place
+        JsonValue::Array(items)
+            if !items.is_empty()
+                && items.iter().all(JsonValue::is_object)
+                && doc.get(key_path).is_some_and(Item::is_array_of_tables) =>
+        {
+            doc.update_array_of_tables(key_path, items.len(), |index, table| apply_json(table, "", &items[index]))
+        }
+        _ => {
+            if key_path.is_empty() {
+                return Err("Config root must be a table".to_string());
+            }
+            let existing = doc.get(key_path).and_then(Item::as_value);
+            let toml_value = json_to_toml(value, existing)?;
+            doc.set(key_path, toml_value)
+        }
+    }
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// Converts a JSON value into a TOML value. `existing` is used as a type hint so that
+/// a float setting written as `1` from the UI stays a float.
+fn json_to_toml(value: &JsonValue, existing: Option<&Value>) -> Result<Value, String> {
+    match value {
+        JsonValue::Bool(b) => Ok(Value::from(*b)),
+        JsonValue::Number(n) => {
+            if let (Some(i), false) = (n.as_i64(), matches!(existing, Some(Value::Float(_)))) {
+                Ok(Value::from(i))
+            } else {
+                n.as_f64()
+                    .map(Value::from)
+                    .ok_or_else(|| format!("Number out of range: {}", n))
+            }
+        }
+        JsonValue::String(s) => Ok(Value::from(s.as_str())),
+        JsonValue::Array(items) => {
+            let mut array = Array::new();
+            for item in items {
+                array.push(json_to_toml(item, None)?);
+            }
+            Ok(Value::Array(array))
+        }
+        JsonValue::Object(map) => {
+            let mut table = InlineTable::new();
+            for (key, item) in map {
+                if !item.is_null() {
+                    table.insert(key, json_to_toml(item, None)?);
+                }
+            }
+            Ok(Value::InlineTable(table))
+        }
+        JsonValue::Null => Err("Null is not a valid TOML value".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const SAMPLE: &str = r#"api_key = "sk-test"
+default_provider = "openrouter"
+default_model = "anthropic/claude-sonnet-4"
+default_temperature = 0.7
+
+[memory]
+backend = "sqlite" # or "markdown"
+auto_save = true
+
+[gateway]
+port = 18789
+host = "127.0.0.1"
+require_pairing = false
+
+[channels_config]
+cli = true
+
+[channels_config.telegram]
+bot_token = "123:abc"
+allowed_users = ["alice"]
+
+[tunnel]
+provider = "none"
+"#;
+
+    fn write_sample(tmp: &TempDir) -> std::path::PathBuf {
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, SAMPLE).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_config_reads_typed_fields_and_extras() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_sample(&tmp);
+
+        let config = load_config(&path).unwrap();
+
+        assert_eq!(config.default_provider.as_deref(), Some("openrouter"));
+        assert_eq!(config.default_temperature, Some(0.7));
+        let gateway = config.gateway.unwrap();
+        assert_eq!(gateway.port, Some(18789));
+        assert_eq!(gateway.require_pairing, Some(false));
+        assert_eq!(config.memory.unwrap().backend.as_deref(), Some("sqlite"));
+        assert!(config.channels_config.unwrap().extra.contains_key("telegram"));
+        assert!(config.extra.contains_key("tunnel"));
+    }
+
+    #[test]
+    fn test_save_config_round_trip_preserves_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_sample(&tmp);
+
+        let config = load_config(&path).unwrap();
+        save_config(&path, &config, false).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), SAMPLE);
+        assert!(!tmp.path().join("backups").exists());
+    }
+
+    #[test]
+    fn test_save_config_keeps_arrays_of_tables() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        let original = format!(
+            "{}\n# Fallback models, tried in order\n[[fallbacks]]\nprovider = \"openai\" # cheapest first\nmodel = \"gpt-4o-mini\"\n\n[[fallbacks]]\nprovider = \"ollama\"\nmodel = \"llama3\"\n",
+            SAMPLE
+        );
+        fs::write(&path, &original).unwrap();
+
+        let mut config = load_config(&path).unwrap();
+        save_config(&path, &config, false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+        assert!(!tmp.path().join("backups").exists());
+
+        let fallbacks = config.extra.get_mut("fallbacks").and_then(toml::Value::as_array_mut).unwrap();
+        fallbacks[1].as_table_mut().unwrap().insert("model".into(), "qwen2".into());
+        fallbacks.push(toml::Value::Table(toml::toml! { provider = "groq" }));
+        save_config(&path, &config, false).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("# Fallback models, tried in order\n[[fallbacks]]\nprovider = \"openai\" # cheapest first\n"));
+        assert!(content.contains("[[fallbacks]]\nprovider = \"ollama\"\nmodel = \"qwen2\"\n"));
+        assert!(content.ends_with("[[fallbacks]]\nprovider = \"groq\"\n"));
+        assert_eq!(load_config(&path).unwrap(), config);
+
+        config.extra.get_mut("fallbacks").and_then(toml::Value::as_array_mut).unwrap().truncate(1);
+        save_config(&path, &config, false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap().matches("[[fallbacks]]").count(), 1);
+    }
+
+    #[test]
+    fn test_writes_back_up_previous_config() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_sample(&tmp);
+
+        set_value(&path, "gateway.port", &json!(18790), false).unwrap();
+
+        let backups_dir = tmp.path().join("backups");
+        let backups = backup::list_backups(&backups_dir).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(backups_dir.join(&backups[0].id)).unwrap(), SAMPLE);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_config_makes_file_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new().unwrap();
+        let path = write_sample(&tmp);
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        set_value(&path, "gateway.port", &json!(18790), false).unwrap();
+
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_save_config_updates_changed_fields_only() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_sample(&tmp);
+
+        let mut config = load_config(&path).unwrap();
+        config.memory.as_mut().unwrap().backend = Some("markdown".to_string());
+        config.default_temperature = Some(1.0);
+        config.api_key = None;
+        save_config(&path, &config, false).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("backend = \"markdown\" # or \"markdown\""));
+        assert!(content.contains("default_temperature = 1.0"));
+        assert!(!content.contains("api_key"));
+        assert!(content.contains("bot_token = \"123:abc\""));
+        assert_eq!(load_config(&path).unwrap(), config);
+    }
+
+    #[test]
+    fn test_get_value_by_key_path() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_sample(&tmp);
+
+        assert_eq!(get_value(&path, "gateway.port").unwrap(), Some(json!(18789)));
+        assert_eq!(
+            get_value(&path, "channels_config.telegram.allowed_users").unwrap(),
+            Some(json!(["alice"]))
+        );
+        assert_eq!(get_value(&path, "gateway.missing").unwrap(), None);
+        assert_eq!(get_value(&path, "gateway..port").unwrap_err().code(), "invalid_input");
+        assert_eq!(get_value(&path, "").unwrap_err().code(), "invalid_input");
+    }
+
+    #[test]
+    fn test_set_value_scalars_tables_and_null() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_sample(&tmp);
+
+        assert!(set_value(&path, "gateway.host", &json!("localhost"), false).unwrap());
+        assert!(!set_value(&path, "gateway.host", &json!("localhost"), false).unwrap());
+        set_value(&path, "default_temperature", &json!(1), false).unwrap();
+        set_value(&path, "channels_config.discord", &json!({"bot_token": "xyz"}), false).unwrap();
+        set_value(&path, "tunnel", &JsonValue::Null, false).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("host = \"localhost\""));
+        assert!(content.contains("default_temperature = 1.0"));
+        assert!(!content.contains("[tunnel]"));
+        // Replacing a scalar with an empty table is still a change
+        assert!(set_value(&path, "channels_config.telegram.bot_token", &json!({}), false).unwrap());
+        assert_eq!(
+            get_value(&path, "channels_config.telegram.bot_token").unwrap(),
+            Some(json!({}))
+        );
+        assert!(fs::read_to_string(&path).unwrap().contains("[channels_config.telegram.bot_token]"));
+        assert_eq!(
+            get_value(&path, "channels_config.discord.bot_token").unwrap(),
+            Some(json!("xyz"))
+        );
+    }
+
+    #[test]
+    fn test_set_value_creates_missing_file_when_forced() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+
+        // A config with only a gateway table lacks the required provider fields
+        assert!(set_value(&path, "gateway.port", &json!(18789), false).is_err());
+        assert!(!path.exists());
+
+        set_value(&path, "gateway.port", &json!(18789), true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[gateway]\nport = 18789\n");
+    }
+
+    #[test]
+    fn test_invalid_changes_are_refused_unless_forced() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_sample(&tmp);
+
+        let err = set_value(&path, "gateway.port", &json!(70000), false).unwrap_err();
+        assert_eq!(err.code(), "config_invalid");
+        assert!(err.detail().contains("gateway.port"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), SAMPLE);
+
+        let mut config = load_config(&path).unwrap();
+        config.memory.as_mut().unwrap().backend = Some("redis".to_string());
+        assert!(save_config(&path, &config, false).is_err());
+        assert_eq!(validate_config(&path, Some(&config)).unwrap().len(), 1);
+
+        save_config(&path, &config, true).unwrap();
+        assert_eq!(validate_config(&path, None).unwrap()[0].path, "memory.backend");
+    }
+}
@@ -0,0 +1,231 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::utils::checksum::{bundled_digest, BinaryDigest};
+use crate::utils::error::EasyClawError;
+use crate::utils::paths::{self, HomeSource, ZeroClawHome, ZEROCLAW_HOME_ENV};
+use crate::utils::platform::{self, bundled_binary_name, select_bundled_binary};
+use crate::utils::process::{ProcessOptions, ProcessRunner, SystemRunner};
+use crate::utils::profiles::DEFAULT_PROFILE;
+
+/// This platform's bundled ZeroClaw binary and the digest it must match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundledBinary {
+    pub path: PathBuf,
+    pub digest: BinaryDigest,
+}
+
+/// Everything the commands need from the outside world: where the ZeroClaw root, the
+/// installed binary, the bundled resources and EasyClaw's settings are, which PATH to
+/// search, and how to run processes. Held in Tauri managed state; tests build their own
+/// with temporary directories and a scripted process runner.
+#[derive(Clone)]
+pub struct ZeroClawEnv {
+    /// A fixed root, or None to resolve it on every call (settings and the active profile
+    /// can change while the app runs).
+    home: Option<ZeroClawHome>,
+    settings_path: Option<PathBuf>,
+    resource_dir: Option<PathBuf>,
+    search_path: Option<OsString>,
+    runner: Arc<dyn ProcessRunner>,
+}
+
+impl ZeroClawEnv {
+    /// The environment of the running app: the resolved ZeroClaw root, the process's PATH
+    /// and real child processes. `resource_dir` is Tauri's resource directory.
+    pub fn system(resource_dir: Option<PathBuf>) -> Self {
+        Self {
+            home: None,
+            settings_path: None,
+            resource_dir,
+            search_path: std::env::var_os("PATH"),
+            runner: Arc::new(SystemRunner),
+        }
+    }
+
+    /// An environment pinned to `root`, with settings in `root/settings.json`, an empty
+    /// PATH and real child processes.
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        Self {
+            settings_path: Some(root.join("settings.json")),
+            home: Some(ZeroClawHome {
+                path: root.clone(),
+                base: root,
+                profile: DEFAULT_PROFILE.to_string(),
+                source: HomeSource::Settings,
+            }),
+            resource_dir: None,
+            search_path: None,
+            runner: Arc::new(SystemRunner),
+        }
+    }
+
+    pub fn with_resource_dir(mut self, resource_dir: impl Into<PathBuf>) -> Self {
+        self.resource_dir = Some(resource_dir.into());
+        self
+    }
+
+    pub fn with_search_path(mut self, search_path: impl Into<OsString>) -> Self {
+        self.search_path = Some(search_path.into());
+        self
+    }
+
+    pub fn with_runner(mut self, runner: Arc<dyn ProcessRunner>) -> Self {
+        self.runner = runner;
+        self
+    }
+
+    /// The ZeroClaw root in use and where it came from.
+    pub fn home(&self) -> Result<ZeroClawHome, String> {
+        match &self.home {
+            Some(home) => Ok(home.clone()),
+            None => paths::resolve_zeroclaw_home(),
+        }
+    }
+
+    /// Root of the active profile: config, workspace, backups.
+    pub fn root(&self) -> Result<PathBuf, String> {
+        self.home().map(|home| home.path)
+    }
+
+    /// The installed binary, shared by all profiles: <base root>/bin/zeroclaw
+    pub fn bin_path(&self) -> Result<PathBuf, String> {
+        Ok(self.home()?.base.join("bin").join(platform::BINARY_NAME))
+    }
+
+    pub fn workspace_dir(&self) -> Result<PathBuf, String> {
+        Ok(self.root()?.join("workspace"))
+    }
+
+    /// Where EasyClaw keeps chat conversations (`workspace/sessions`, created by onboard).
+    pub fn sessions_dir(&self) -> Result<PathBuf, String> {
+        Ok(self.workspace_dir()?.join("sessions"))
+    }
+
+    /// The agent's memory notes (`workspace/memory`).
+    pub fn memory_dir(&self) -> Result<PathBuf, String> {
+        Ok(self.workspace_dir()?.join("memory"))
+    }
+
+    pub fn config_path(&self) -> Result<PathBuf, String> {
+        Ok(self.root()?.join("config.toml"))
+    }
+
+    pub fn backups_dir(&self) -> Result<PathBuf, String> {
+        Ok(self.root()?.join("backups"))
+    }
+
+    /// Default destination of conversation archives.
+    pub fn exports_dir(&self) -> Result<PathBuf, String> {
+        Ok(self.root()?.join("exports"))
+    }
+
+    /// EasyClaw's own settings file (see `paths::settings_file_path`).
+    pub fn settings_path(&self) -> Result<PathBuf, String> {
+        match &self.settings_path {
+            Some(path) => Ok(path.clone()),
+            None => paths::settings_file_path(),
+        }
+    }
+
+    /// The directory with the binaries bundled with the app (`resources/bin`).
+    pub fn bundled_bin_dir(&self) -> Result<PathBuf, String> {
+        let resource_dir = self
+            .resource_dir
+            .as_ref()
+            .ok_or_else(|| "Failed to get resource directory".to_string())?;
+        Ok(resource_dir.join("resources").join("bin"))
+    }
+
+    /// Picks this platform's bundled binary and its build-time digest.
+    pub fn bundled_binary(&self) -> Result<BundledBinary, EasyClawError> {
+        Ok(BundledBinary {
+            path: select_bundled_binary(&self.bundled_bin_dir()?)?,
+            digest: bundled_digest(&bundled_binary_name())?,
+        })
+    }
+
+    /// Finds `name` in the PATH directories (searched in-process rather than spawning `which`).
+    pub fn find_on_path(&self, name: &str) -> Option<PathBuf> {
+        let search_path = self.search_path.as_ref()?;
+        std::env::split_paths(search_path)
+            .map(|dir| dir.join(name))
+            .find(|candidate| candidate.is_file())
+    }
+
+    pub fn runner(&self) -> &dyn ProcessRunner {
+        self.runner.as_ref()
+    }
+
+    /// Options for running zeroclaw against this root (`ZEROCLAW_HOME`).
+    pub fn zeroclaw_options(&self, timeout: Duration) -> ProcessOptions {
+        let options = ProcessOptions::with_timeout(timeout);
+        match self.root() {
+            Ok(root) => options.with_env(ZEROCLAW_HOME_ENV, root),
+            Err(_) => options,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_with_root_derives_paths() {
+        let env = ZeroClawEnv::with_root("/data/zc");
+
+        assert_eq!(env.root().unwrap(), PathBuf::from("/data/zc"));
+        assert_eq!(env.bin_path().unwrap(), PathBuf::from("/data/zc/bin").join(platform::BINARY_NAME));
+        assert_eq!(env.config_path().unwrap(), PathBuf::from("/data/zc/config.toml"));
+        assert_eq!(env.sessions_dir().unwrap(), PathBuf::from("/data/zc/workspace/sessions"));
+        assert_eq!(env.memory_dir().unwrap(), PathBuf::from("/data/zc/workspace/memory"));
+        assert_eq!(env.exports_dir().unwrap(), PathBuf::from("/data/zc/exports"));
+        assert_eq!(env.settings_path().unwrap(), PathBuf::from("/data/zc/settings.json"));
+        assert!(env.bundled_bin_dir().is_err());
+        assert_eq!(
+            env.with_resource_dir("/app").bundled_bin_dir().unwrap(),
+            PathBuf::from("/app/resources/bin")
+        );
+    }
+
+    #[test]
+    fn test_system_paths_are_under_resolved_root() {
+        let env = ZeroClawEnv::system(None);
+        let home = env.home().unwrap();
+
+        assert!(env.workspace_dir().unwrap().starts_with(&home.path));
+        assert!(env.config_path().unwrap().ends_with("config.toml"));
+        assert!(env.backups_dir().unwrap().starts_with(&home.path));
+        assert!(env.bin_path().unwrap().starts_with(home.base.join("bin")));
+        assert!(!env.settings_path().unwrap().starts_with(&home.path));
+    }
+
+    #[test]
+    fn test_find_on_path_searches_in_order() {
+        let tmp = TempDir::new().unwrap();
+        let (first, second) = (tmp.path().join("a"), tmp.path().join("b"));
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        fs::write(second.join("zeroclaw"), "").unwrap();
+
+        let search_path = std::env::join_paths([&first, &second]).unwrap();
+        let env = ZeroClawEnv::with_root(tmp.path()).with_search_path(search_path);
+
+        assert_eq!(env.find_on_path("zeroclaw"), Some(second.join("zeroclaw")));
+        assert_eq!(env.find_on_path("missing"), None);
+        assert_eq!(ZeroClawEnv::with_root(tmp.path()).find_on_path("zeroclaw"), None);
+    }
+
+    #[test]
+    fn test_zeroclaw_options_set_home() {
+        let env = ZeroClawEnv::with_root("/data/zc");
+        let options = env.zeroclaw_options(Duration::from_secs(1));
+        assert_eq!(options.env, vec![(ZEROCLAW_HOME_ENV.into(), "/data/zc".into())]);
+    }
+}
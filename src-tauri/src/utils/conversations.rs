@@ -0,0 +1,350 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::error::EasyClawError;
+use crate::utils::fs::{append_lines, PRIVATE_FILE_MODE};
+
+const FILE_PREFIX: &str = "chat-";
+const FILE_SUFFIX: &str = ".jsonl";
+
+/// Longest conversation id accepted; ids become file names.
+const MAX_ID_LEN: usize = 64;
+
+/// Longest title taken from the first message, in characters.
+const TITLE_MAX_CHARS: usize = 40;
+
+const DEFAULT_TITLE: &str = "New conversation";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    User,
+    Assistant,
+}
+
+/// Whether a stored message is a reply or the error shown in its place.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageStatus {
+    #[default]
+    Done,
+    Error,
+}
+
+/// One finished message of a conversation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredMessage {
+    pub id: String,
+    pub role: ChatRole,
+    pub content: String,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: i64,
+    #[serde(default)]
+    pub status: MessageStatus,
+}
+
+/// A conversation without its messages, for listing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConversationSummary {
+    pub id: String,
+    pub title: String,
+    /// Creation time in RFC 3339 (UTC).
+    pub created_at: String,
+    /// Time of the last change in RFC 3339 (UTC).
+    pub updated_at: String,
+    pub message_count: usize,
+}
+
+/// A conversation with all its messages, oldest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conversation {
+    pub id: String,
+    pub title: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub messages: Vec<StoredMessage>,
+}
+
+impl Conversation {
+    pub fn summary(&self) -> ConversationSummary {
+        ConversationSummary {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            created_at: self.created_at.clone(),
+            updated_at: self.updated_at.clone(),
+            message_count: self.messages.len(),
+        }
+    }
+}
+
+/// A line of a conversation file (`chat-<id>.jsonl`). Files are only ever appended to,
+/// one record per line, so a crash loses at most the line being written; a partial
+/// line is skipped when reading.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record {
+    Created { title: String, created_at: String },
+    Message(StoredMessage),
+    Renamed { title: String },
+}
+
+/// Checks that `id` is usable as a file name: 1-64 ASCII letters, digits, `-` or `_`.
+pub fn validate_conversation_id(id: &str) -> Result<(), EasyClawError> {
+    let valid = !id.is_empty()
+        && id.len() <= MAX_ID_LEN
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(EasyClawError::InvalidInput(format!(
+            "Invalid conversation id '{}': use up to {} letters, digits, '-' or '_'",
+            id, MAX_ID_LEN
+        )))
+    }
+}
+
+/// Lists the conversations in `sessions_dir`, most recently changed first. A missing
+/// directory yields an empty list; unreadable files are skipped.
+pub fn list_conversations(sessions_dir: &Path) -> Result<Vec<ConversationSummary>, EasyClawError> {
+    let mut conversations: Vec<ConversationSummary> = conversation_files(sessions_dir)?
+        .into_iter()
+        .filter_map(|(id, path)| match read_conversation(&path, &id) {
+            Ok(conversation) => Some(conversation.summary()),
+            Err(e) => {
+                warn!("[list_conversations] Skipping {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect();
+    conversations.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then_with(|| b.id.cmp(&a.id)));
+    Ok(conversations)
+}
+
+/// The conversation files in `sessions_dir` and their ids. A missing directory yields
+/// an empty list; other files (e.g. ZeroClaw's own sessions) are ignored.
+pub fn conversation_files(sessions_dir: &Path) -> Result<Vec<(String, PathBuf)>, EasyClawError> {
+    if !sessions_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(sessions_dir)
+        .map_err(|e| EasyClawError::io(format!("Failed to read {}", sessions_dir.display()), &e))?;
+
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let id = name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_SUFFIX)?.to_string();
+            Some((id, entry.path()))
+        })
+        .collect())
+}
+
+/// Loads conversation `id` with all its messages.
+pub fn load_conversation(sessions_dir: &Path, id: &str) -> Result<Conversation, EasyClawError> {
+    read_conversation(&existing_path(sessions_dir, id)?, id)
+}
+
+/// Appends a finished message to conversation `id`, creating the conversation (titled
+/// after the message) if it doesn't exist yet.
+pub fn append_message(
+    sessions_dir: &Path,
+    id: &str,
+    message: StoredMessage,
+) -> Result<ConversationSummary, EasyClawError> {
+    validate_conversation_id(id)?;
+    let path = conversation_path(sessions_dir, id);
+
+    let mut records = Vec::new();
+    if !path.exists() {
+        fs::create_dir_all(sessions_dir)
+            .map_err(|e| EasyClawError::io(format!("Failed to create {}", sessions_dir.display()), &e))?;
+        records.push(Record::Created { title: title_from(&message.content), created_at: now() });
+        info!("[append_message] Starting conversation {}", id);
+    }
+    records.push(Record::Message(message));
+    append_records(&path, &records)?;
+
+    Ok(read_conversation(&path, id)?.summary())
+}
+
+/// Sets the title of conversation `id`.
+pub fn rename_conversation(sessions_dir: &Path, id: &str, title: &str) -> Result<ConversationSummary, EasyClawError> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(EasyClawError::InvalidInput("Conversation title is empty".to_string()));
+    }
+    let path = existing_path(sessions_dir, id)?;
+    append_records(&path, &[Record::Renamed { title: title.to_string() }])?;
+    Ok(read_conversation(&path, id)?.summary())
+}
+
+/// Deletes conversation `id`.
+pub fn delete_conversation(sessions_dir: &Path, id: &str) -> Result<(), EasyClawError> {
+    let path = existing_path(sessions_dir, id)?;
+    fs::remove_file(&path).map_err(|e| EasyClawError::io(format!("Failed to delete {}", path.display()), &e))?;
+    info!("[delete_conversation] Deleted conversation {}", id);
+    Ok(())
+}
+
+fn conversation_path(sessions_dir: &Path, id: &str) -> PathBuf {
+    sessions_dir.join(format!("{}{}{}", FILE_PREFIX, id, FILE_SUFFIX))
+}
+
+fn existing_path(sessions_dir: &Path, id: &str) -> Result<PathBuf, EasyClawError> {
+    validate_conversation_id(id)?;
+    let path = conversation_path(sessions_dir, id);
+    if !path.is_file() {
+        return Err(EasyClawError::NotFound(format!("Conversation not found: {}", id)));
+    }
+    Ok(path)
+}
+
+fn append_records(path: &Path, records: &[Record]) -> Result<(), EasyClawError> {
+    let mut lines = String::new();
+    for record in records {
+        let line = serde_json::to_string(record)
+            .map_err(|e| EasyClawError::Internal(format!("Failed to serialize conversation record: {}", e)))?;
+        lines.push_str(&line);
+        lines.push('\n');
+    }
+    append_lines(path, &lines, Some(PRIVATE_FILE_MODE))
+}
+
+/// Reads the conversation file at `path`, skipping lines that can't be parsed.
+pub fn read_conversation(path: &Path, id: &str) -> Result<Conversation, EasyClawError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| EasyClawError::io(format!("Failed to read {}", path.display()), &e))?;
+    let updated_at = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map(rfc3339)
+        .unwrap_or_else(|_| now());
+
+    let mut conversation = Conversation {
+        id: id.to_string(),
+        title: DEFAULT_TITLE.to_string(),
+        created_at: updated_at.clone(),
+        updated_at,
+        messages: Vec::new(),
+    };
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Record>(line) {
+            Ok(Record::Created { title, created_at }) => {
+                conversation.title = title;
+                conversation.created_at = created_at;
+            }
+            Ok(Record::Message(message)) => conversation.messages.push(message),
+            Ok(Record::Renamed { title }) => conversation.title = title,
+            Err(e) => warn!("[read_conversation] Skipping line {} of {}: {}", index + 1, path.display(), e),
+        }
+    }
+    Ok(conversation)
+}
+
+/// The first line of the first message, shortened to `TITLE_MAX_CHARS`.
+fn title_from(content: &str) -> String {
+    let line = content.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+    if line.is_empty() {
+        return DEFAULT_TITLE.to_string();
+    }
+    if line.chars().count() <= TITLE_MAX_CHARS {
+        return line.to_string();
+    }
+    let mut title: String = line.chars().take(TITLE_MAX_CHARS).collect();
+    title.push('…');
+    title
+}
+
+fn now() -> String {
+    rfc3339(SystemTime::now())
+}
+
+fn rfc3339(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn message(id: &str, role: ChatRole, content: &str) -> StoredMessage {
+        StoredMessage { id: id.into(), role, content: content.into(), timestamp: 1_700_000_000_000, status: MessageStatus::Done }
+    }
+
+    #[test]
+    fn test_append_creates_and_extends_conversation() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("sessions");
+
+        let summary = append_message(&dir, "c1", message("m1", ChatRole::User, "\n  How do I set up Telegram?\nDetails")).unwrap();
+        assert_eq!(summary.title, "How do I set up Telegram?");
+        assert_eq!(summary.message_count, 1);
+
+        let mut reply = message("m2", ChatRole::Assistant, "Run zeroclaw channel add");
+        reply.status = MessageStatus::Error;
+        append_message(&dir, "c1", reply.clone()).unwrap();
+
+        let conversation = load_conversation(&dir, "c1").unwrap();
+        assert_eq!(conversation.messages, vec![message("m1", ChatRole::User, "\n  How do I set up Telegram?\nDetails"), reply]);
+        assert_eq!(conversation.created_at, summary.created_at);
+        assert_eq!(list_conversations(&dir).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_partial_last_line_loses_only_that_message() {
+        let tmp = TempDir::new().unwrap();
+        append_message(tmp.path(), "c1", message("m1", ChatRole::User, "hi")).unwrap();
+        // Simulate a crash in the middle of writing the next record
+        let mut file = OpenOptions::new().append(true).open(conversation_path(tmp.path(), "c1")).unwrap();
+        file.write_all(br#"{"type":"message","id":"m2","ro"#).unwrap();
+
+        append_message(tmp.path(), "c1", message("m3", ChatRole::User, "again")).unwrap();
+
+        let ids: Vec<String> = load_conversation(tmp.path(), "c1").unwrap().messages.into_iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec!["m1", "m3"]);
+    }
+
+    #[test]
+    fn test_rename_list_and_delete() {
+        let tmp = TempDir::new().unwrap();
+        append_message(tmp.path(), "c1", message("m1", ChatRole::User, &"长".repeat(50))).unwrap();
+        append_message(tmp.path(), "c2", message("m1", ChatRole::User, "second")).unwrap();
+        fs::write(tmp.path().join("zeroclaw-session.jsonl"), "not ours").unwrap();
+
+        assert_eq!(load_conversation(tmp.path(), "c1").unwrap().title, format!("{}…", "长".repeat(40)));
+        let renamed = rename_conversation(tmp.path(), "c1", "  Renamed  ").unwrap();
+        assert_eq!(renamed.title, "Renamed");
+        assert_eq!(rename_conversation(tmp.path(), "c1", " ").unwrap_err().code(), "invalid_input");
+
+        let mut ids: Vec<String> = list_conversations(tmp.path()).unwrap().into_iter().map(|c| c.id).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["c1", "c2"]);
+
+        delete_conversation(tmp.path(), "c1").unwrap();
+        assert_eq!(load_conversation(tmp.path(), "c1").unwrap_err().code(), "not_found");
+        assert_eq!(delete_conversation(tmp.path(), "c1").unwrap_err().code(), "not_found");
+        assert_eq!(list_conversations(tmp.path()).unwrap().len(), 1);
+        assert!(list_conversations(&tmp.path().join("missing")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_unsafe_ids() {
+        let tmp = TempDir::new().unwrap();
+        for id in ["", "../escape", "a/b", &"x".repeat(65)] {
+            let err = append_message(tmp.path(), id, message("m1", ChatRole::User, "hi")).unwrap_err();
+            assert_eq!(err.code(), "invalid_input", "{}", id);
+        }
+        assert!(validate_conversation_id("1700000000000-ab12cd3").is_ok());
+    }
+}
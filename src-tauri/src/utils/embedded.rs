@@ -0,0 +1,230 @@
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use log::{info, warn};
+
+use crate::utils::error::EasyClawError;
+
+/// Number of recent output lines kept from the embedded gateway.
+const OUTPUT_HISTORY: usize = 200;
+
+/// Snapshot of the embedded gateway process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedStatus {
+    pub pid: u32,
+    pub running: bool,
+    /// Exit code once the process has exited (None if killed by a signal or still running).
+    pub exit_code: Option<i32>,
+    pub uptime_secs: u64,
+    /// Last non-empty stderr line, useful as an error hint after a crash.
+    pub last_error: Option<String>,
+}
+
+struct EmbeddedProcess {
+    child: Child,
+    started_at: Instant,
+    output: Arc<Mutex<VecDeque<String>>>,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+/// Runs the ZeroClaw daemon as a child process of EasyClaw, for systems without a
+/// usable service manager. Held in Tauri managed state; the child is killed when
+/// this is stopped or dropped.
+#[derive(Default)]
+pub struct EmbeddedGateway {
+    process: Mutex<Option<EmbeddedProcess>>,
+}
+
+impl EmbeddedGateway {
+    /// Spawns `bin_path args...` with `env` added to its environment, unless a child is
+    /// already running. Returns its PID.
+    pub fn start(&self, bin_path: &Path, args: &[&str], env: &[(OsString, OsString)]) -> Result<u32, EasyClawError> {
+        let mut process = self.lock();
+        if let Some(existing) = process.as_mut() {
+            if existing.child.try_wait().ok().flatten().is_none() {
+                info!("[embedded_gateway] Already running with pid {}", existing.child.id());
+                return Ok(existing.child.id());
+            }
+        }
+
+        info!("[embedded_gateway] Spawning {:?} {:?}", bin_path, args);
+        let mut child = Command::new(bin_path)
+            .args(args)
+            .envs(env.iter().map(|(key, value)| (key, value)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| EasyClawError::io("Failed to spawn embedded gateway", &e))?;
+
+        let output = Arc::new(Mutex::new(VecDeque::with_capacity(OUTPUT_HISTORY)));
+        let last_error = Arc::new(Mutex::new(None));
+        if let Some(stdout) = child.stdout.take() {
+            capture_lines(stdout, "stdout", output.clone(), None);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            capture_lines(stderr, "stderr", output.clone(), Some(last_error.clone()));
+        }
+
+        let pid = child.id();
+        info!("[embedded_gateway] Started with pid {}", pid);
+        *process = Some(EmbeddedProcess {
+            child,
+            started_at: Instant::now(),
+            output,
+            last_error,
+        });
+        Ok(pid)
+    }
+
+    /// Kills the child process if it is still running. Returns true if one was stopped.
+    pub fn stop(&self) -> Result<bool, EasyClawError> {
+        let Some(mut process) = self.lock().take() else {
+            return Ok(false);
+        };
+        if process.child.try_wait().ok().flatten().is_some() {
+            return Ok(false);
+        }
+
+        let pid = process.child.id();
+        process
+            .child
+            .kill()
+            .map_err(|e| EasyClawError::io(format!("Failed to stop embedded gateway (pid {})", pid), &e))?;
+        let _ = process.child.wait();
+        info!("[embedded_gateway] Stopped pid {}", pid);
+        Ok(true)
+    }
+
+    /// Returns true if a child process is currently running.
+    pub fn is_running(&self) -> bool {
+        self.status().is_some_and(|status| status.running)
+    }
+
+    /// Reports the child process, or None if none was started.
+    pub fn status(&self) -> Option<EmbeddedStatus> {
+        let mut process = self.lock();
+        let process = process.as_mut()?;
+        let exit = process.child.try_wait().ok().flatten();
+        Some(EmbeddedStatus {
+            pid: process.child.id(),
+            running: exit.is_none(),
+            exit_code: exit.and_then(|status| status.code()),
+            uptime_secs: process.started_at.elapsed().as_secs(),
+            last_error: process.last_error.lock().ok().and_then(|e| e.clone()),
+        })
+    }
+
+    /// Returns the most recent output lines, prefixed with the stream name.
+    pub fn recent_output(&self) -> Vec<String> {
+        self.lock()
+            .as_ref()
+            .and_then(|process| process.output.lock().ok().map(|lines| lines.iter().cloned().collect()))
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<EmbeddedProcess>> {
+        self.process.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Drop for EmbeddedGateway {
+    fn drop(&mut self) {
+        if let Err(e) = self.stop() {
+            warn!("[embedded_gateway] {}", e);
+        }
+    }
+}
+
+/// Forwards each line of `reader` to the log and the shared output history.
+fn capture_lines<R: Read + Send + 'static>(
+    reader: R,
+    stream: &'static str,
+    output: Arc<Mutex<VecDeque<String>>>,
+    last_error: Option<Arc<Mutex<Option<String>>>>,
+) {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            if stream == "stderr" {
+                warn!("[embedded_gateway] {}: {}", stream, line);
+            } else {
+                info!("[embedded_gateway] {}: {}", stream, line);
+            }
+            if let (Some(last_error), false) = (&last_error, line.trim().is_empty()) {
+                if let Ok(mut last_error) = last_error.lock() {
+                    *last_error = Some(line.trim().to_string());
+                }
+            }
+            if let Ok(mut output) = output.lock() {
+                if output.len() == OUTPUT_HISTORY {
+                    output.pop_front();
+                }
+                output.push_back(format!("[{}] {}", stream, line));
+            }
+        }
+    });
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn create_script(dir: &Path, body: &str) -> PathBuf {
+        let bin = dir.join("zeroclaw");
+        fs::write(&bin, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&bin, fs::Permissions::from_mode(0o755)).unwrap();
+        bin
+    }
+
+    fn wait_for(mut condition: impl FnMut() -> bool) {
+        for _ in 0..100 {
+            if condition() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        panic!("condition not met in time");
+    }
+
+    #[test]
+    fn test_start_captures_output_and_stop_kills_child() {
+        let tmp = TempDir::new().unwrap();
+        let bin = create_script(tmp.path(), "echo \"listening on $1\"\nexec sleep 30");
+        let gateway = EmbeddedGateway::default();
+
+        let pid = gateway.start(&bin, &["daemon"], &[]).unwrap();
+        assert_eq!(gateway.start(&bin, &["daemon"], &[]).unwrap(), pid);
+        assert!(gateway.is_running());
+        wait_for(|| gateway.recent_output() == vec!["[stdout] listening on daemon".to_string()]);
+
+        assert!(gateway.stop().unwrap());
+        assert!(!gateway.is_running());
+        assert!(gateway.status().is_none());
+        assert!(!gateway.stop().unwrap());
+    }
+
+    #[test]
+    fn test_status_reports_crash_with_last_stderr_line() {
+        let tmp = TempDir::new().unwrap();
+        let bin = create_script(tmp.path(), "echo \"bind failed: address in use\" >&2\nexit 2");
+        let gateway = EmbeddedGateway::default();
+
+        gateway.start(&bin, &["daemon"], &[]).unwrap();
+        wait_for(|| gateway.status().is_some_and(|s| !s.running && s.last_error.is_some()));
+
+        let status = gateway.status().unwrap();
+        assert_eq!(status.exit_code, Some(2));
+        assert_eq!(status.last_error.as_deref(), Some("bind failed: address in use"));
+    }
+}
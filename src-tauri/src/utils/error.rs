@@ -0,0 +1,280 @@
+use std::fmt;
+use std::io;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::utils::process::ProcessError;
+
+/// Error returned by every Tauri command. Serialized as
+/// `{ code, message, detail, remedy }`: `code` is stable so the UI can localize and react
+/// per case, `message` is an English summary, `detail` the underlying error text and
+/// `remedy` a suggested next step. `GatewayRejected` with a status adds `status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EasyClawError {
+    /// No zeroclaw binary is installed or on PATH.
+    BinaryMissing(String),
+    /// The app has no ZeroClaw build for this OS and architecture.
+    UnsupportedPlatform(String),
+    /// A ZeroClaw binary doesn't match its recorded checksum.
+    IntegrityCheckFailed(String),
+    /// `zeroclaw onboard` failed.
+    OnboardFailed(String),
+    /// A `zeroclaw service` action failed.
+    ServiceFailed(String),
+    /// The gateway can't be reached.
+    GatewayUnreachable(String),
+    /// The gateway answered a request with an error. `status` is the HTTP status when the
+    /// gateway gave no message of its own, so the UI can explain the status instead.
+    GatewayRejected { status: Option<u16>, detail: String },
+    /// A file or directory couldn't be read or written.
+    PermissionDenied(String),
+    /// No free port for the gateway.
+    PortInUse(String),
+    /// config.toml can't be parsed or fails validation.
+    ConfigInvalid(String),
+    /// A process or request didn't finish in time.
+    Timeout(String),
+    /// A requested profile, version, backup or file doesn't exist.
+    NotFound(String),
+    /// The request itself is invalid, e.g. a malformed profile name.
+    InvalidInput(String),
+    /// Anything else.
+    Internal(String),
+}
+
+impl EasyClawError {
+    /// Stable identifier of the error kind.
+    pub fn code(&self) -> &'static str {
+        match self {
+            EasyClawError::BinaryMissing(_) => "binary_missing",
+            EasyClawError::UnsupportedPlatform(_) => "unsupported_platform",
+            EasyClawError::IntegrityCheckFailed(_) => "integrity_check_failed",
+            EasyClawError::OnboardFailed(_) => "onboard_failed",
+            EasyClawError::ServiceFailed(_) => "service_failed",
+            EasyClawError::GatewayUnreachable(_) => "gateway_unreachable",
+            EasyClawError::GatewayRejected { .. } => "gateway_rejected",
+            EasyClawError::PermissionDenied(_) => "permission_denied",
+            EasyClawError::PortInUse(_) => "port_in_use",
+            EasyClawError::ConfigInvalid(_) => "config_invalid",
+            EasyClawError::Timeout(_) => "timeout",
+            EasyClawError::NotFound(_) => "not_found",
+            EasyClawError::InvalidInput(_) => "invalid_input",
+            EasyClawError::Internal(_) => "internal",
+        }
+    }
+
+    /// Short English summary of the error kind.
+    pub fn message(&self) -> &'static str {
+        match self {
+            EasyClawError::BinaryMissing(_) => "ZeroClaw is not installed",
+            EasyClawError::UnsupportedPlatform(_) => "No ZeroClaw build for this platform",
+            EasyClawError::IntegrityCheckFailed(_) => "ZeroClaw binary failed its integrity check",
+            EasyClawError::OnboardFailed(_) => "ZeroClaw setup failed",
+            EasyClawError::ServiceFailed(_) => "ZeroClaw service command failed",
+            EasyClawError::GatewayUnreachable(_) => "ZeroClaw gateway is not reachable",
+            EasyClawError::GatewayRejected { .. } => "ZeroClaw gateway returned an error",
+            EasyClawError::PermissionDenied(_) => "Permission denied",
+            EasyClawError::PortInUse(_) => "No free port for the gateway",
+            EasyClawError::ConfigInvalid(_) => "ZeroClaw config is invalid",
+            EasyClawError::Timeout(_) => "The operation timed out",
+            EasyClawError::NotFound(_) => "Not found",
+            EasyClawError::InvalidInput(_) => "Invalid request",
+            EasyClawError::Internal(_) => "Unexpected error",
+        }
+    }
+
+    /// The underlying error text.
+    pub fn detail(&self) -> &str {
+        match self {
+            EasyClawError::BinaryMissing(detail)
+            | EasyClawError::UnsupportedPlatform(detail)
+            | EasyClawError::IntegrityCheckFailed(detail)
+            | EasyClawError::OnboardFailed(detail)
+            | EasyClawError::ServiceFailed(detail)
+            | EasyClawError::GatewayUnreachable(detail)
+            | EasyClawError::GatewayRejected { detail, .. }
+            | EasyClawError::PermissionDenied(detail)
+            | EasyClawError::PortInUse(detail)
+            | EasyClawError::ConfigInvalid(detail)
+            | EasyClawError::Timeout(detail)
+            | EasyClawError::NotFound(detail)
+            | EasyClawError::InvalidInput(detail)
+            | EasyClawError::Internal(detail) => detail,
+        }
+    }
+
+    /// What the user can do about it, if anything.
+    pub fn remedy(&self) -> Option<&'static str> {
+        match self {
+            EasyClawError::BinaryMissing(_) => Some("Run the initialization again to install ZeroClaw"),
+            EasyClawError::UnsupportedPlatform(_) => {
+                Some("Download the EasyClaw build for your operating system and architecture")
+            }
+            EasyClawError::IntegrityCheckFailed(_) => {
+                Some("Reinstall EasyClaw; the bundled ZeroClaw binary is damaged or was modified")
+            }
+            EasyClawError::OnboardFailed(_) => Some("Check the ZeroClaw output, then retry"),
+            EasyClawError::ServiceFailed(_) => Some("Retry, or switch the gateway mode to embedded in settings"),
+            EasyClawError::GatewayUnreachable(_) => Some("Restart the gateway and check that its port isn't blocked"),
+            EasyClawError::GatewayRejected { .. } => Some("Check the model provider settings in the ZeroClaw config"),
+            EasyClawError::PermissionDenied(_) => {
+                Some("Make sure EasyClaw may read and write the ZeroClaw directory")
+            }
+            EasyClawError::PortInUse(_) => Some("Close the programs using the gateway ports, or set another port"),
+            EasyClawError::ConfigInvalid(_) => Some("Fix the reported fields or restore a config backup"),
+            EasyClawError::Timeout(_) => Some("Retry; if it keeps timing out, check the system load"),
+            EasyClawError::NotFound(_) | EasyClawError::InvalidInput(_) | EasyClawError::Internal(_) => None,
+        }
+    }
+
+    /// Prefixes the detail with what was being done, keeping the code.
+    pub fn with_context(self, context: impl fmt::Display) -> Self {
+        let detail = format!("{}: {}", context, self.detail());
+        match self {
+            EasyClawError::BinaryMissing(_) => EasyClawError::BinaryMissing(detail),
+            EasyClawError::UnsupportedPlatform(_) => EasyClawError::UnsupportedPlatform(detail),
+            EasyClawError::IntegrityCheckFailed(_) => EasyClawError::IntegrityCheckFailed(detail),
+            EasyClawError::OnboardFailed(_) => EasyClawError::OnboardFailed(detail),
+            EasyClawError::ServiceFailed(_) => EasyClawError::ServiceFailed(detail),
+            EasyClawError::GatewayUnreachable(_) => EasyClawError::GatewayUnreachable(detail),
+            EasyClawError::GatewayRejected { status, .. } => EasyClawError::GatewayRejected { status, detail },
+            EasyClawError::PermissionDenied(_) => EasyClawError::PermissionDenied(detail),
+            EasyClawError::PortInUse(_) => EasyClawError::PortInUse(detail),
+            EasyClawError::ConfigInvalid(_) => EasyClawError::ConfigInvalid(detail),
+            EasyClawError::Timeout(_) => EasyClawError::Timeout(detail),
+            EasyClawError::NotFound(_) => EasyClawError::NotFound(detail),
+            EasyClawError::InvalidInput(_) => EasyClawError::InvalidInput(detail),
+            EasyClawError::Internal(_) => EasyClawError::Internal(detail),
+        }
+    }
+
+    /// Maps an I/O error on `what` (e.g. `Failed to read /path`): permission and
+    /// missing-file errors get their own codes.
+    pub fn io(what: impl fmt::Display, e: &io::Error) -> Self {
+        let detail = format!("{}: {}", what, e);
+        match e.kind() {
+            io::ErrorKind::PermissionDenied => EasyClawError::PermissionDenied(detail),
+            io::ErrorKind::NotFound => EasyClawError::NotFound(detail),
+            _ => EasyClawError::Internal(detail),
+        }
+    }
+
+    /// Maps a failed zeroclaw run: timeouts and permission errors get their own codes,
+    /// everything else becomes `failed` (e.g. `EasyClawError::OnboardFailed`).
+    pub fn process(e: ProcessError, failed: impl FnOnce(String) -> Self) -> Self {
+        match &e {
+            ProcessError::Timeout { .. } => EasyClawError::Timeout(e.to_string()),
+            ProcessError::Spawn { kind: io::ErrorKind::PermissionDenied, .. } => {
+                EasyClawError::PermissionDenied(e.to_string())
+            }
+            _ => failed(e.to_string()),
+        }
+    }
+}
+
+/// Errors of helpers that still report plain text. Those never carry a specific code:
+/// helpers whose failures need one return `EasyClawError` themselves.
+impl From<String> for EasyClawError {
+    fn from(detail: String) -> Self {
+        EasyClawError::Internal(detail)
+    }
+}
+
+impl From<&str> for EasyClawError {
+    fn from(detail: &str) -> Self {
+        detail.to_string().into()
+    }
+}
+
+/// For helpers that still return `Result<_, String>` and call typed ones.
+impl From<EasyClawError> for String {
+    fn from(e: EasyClawError) -> Self {
+        e.to_string()
+    }
+}
+
+impl fmt::Display for EasyClawError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.detail())
+    }
+}
+
+impl std::error::Error for EasyClawError {}
+
+impl Serialize for EasyClawError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let status = match self {
+            EasyClawError::GatewayRejected { status, .. } => *status,
+            _ => None,
+        };
+        let mut state = serializer.serialize_struct("EasyClawError", 4 + usize::from(status.is_some()))?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.serialize_field("detail", self.detail())?;
+        state.serialize_field("remedy", &self.remedy())?;
+        if let Some(status) = status {
+            state.serialize_field("status", &status)?;
+        }
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::process::ProcessOutput;
+    use std::time::Duration;
+
+    #[test]
+    fn test_serializes_code_message_detail_and_remedy() {
+        let error = EasyClawError::PortInUse("No free gateway port on 127.0.0.1".into());
+
+        let json = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "code": "port_in_use",
+                "message": "No free port for the gateway",
+                "detail": "No free gateway port on 127.0.0.1",
+                "remedy": "Close the programs using the gateway ports, or set another port",
+            })
+        );
+        let json = serde_json::to_value(EasyClawError::NotFound("Backup not found: x".into())).unwrap();
+        assert_eq!(json["remedy"], serde_json::Value::Null);
+        assert!(json.get("status").is_none());
+
+        let rejected = EasyClawError::GatewayRejected { status: Some(503), detail: "Gateway returned HTTP 503".into() };
+        assert_eq!(serde_json::to_value(&rejected).unwrap()["status"], 503);
+    }
+
+    #[test]
+    fn test_from_string_never_guesses_from_wording() {
+        let tampered = "/tmp/zeroclaw failed integrity check: SHA-256 is 00, expected ff (modified or corrupted)";
+        assert_eq!(EasyClawError::from(tampered).code(), "internal");
+        let denied = "Failed to write /etc/x: Permission denied (os error 13)";
+        assert_eq!(EasyClawError::from(denied).code(), "internal");
+    }
+
+    #[test]
+    fn test_io_and_process_mapping() {
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        assert_eq!(EasyClawError::io("Failed to read x", &denied).code(), "permission_denied");
+        let missing = io::Error::from(io::ErrorKind::NotFound);
+        assert_eq!(EasyClawError::io("Failed to read x", &missing).code(), "not_found");
+
+        let timeout = ProcessError::Timeout { program: "zeroclaw onboard".into(), timeout: Duration::from_secs(5) };
+        assert_eq!(EasyClawError::process(timeout, EasyClawError::OnboardFailed).code(), "timeout");
+        let spawn = ProcessError::Spawn {
+            program: "zeroclaw onboard".into(),
+            message: "Zugriff verweigert".into(),
+            kind: io::ErrorKind::PermissionDenied,
+        };
+        assert_eq!(EasyClawError::process(spawn, EasyClawError::OnboardFailed).code(), "permission_denied");
+        let failed = ProcessError::NonZeroExit { program: "zeroclaw onboard".into(), output: ProcessOutput::default() };
+        assert_eq!(EasyClawError::process(failed, EasyClawError::OnboardFailed).code(), "onboard_failed");
+
+        let error = EasyClawError::PortInUse("no port".into()).with_context("Switched profile, but");
+        assert_eq!(error, EasyClawError::PortInUse("Switched profile, but: no port".into()));
+    }
+}
@@ -0,0 +1,394 @@
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Datelike, Local, Timelike, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::utils::conversations::{self, ChatRole, Conversation, MessageStatus, StoredMessage};
+use crate::utils::error::EasyClawError;
+use crate::utils::fs::{write_atomic, PRIVATE_FILE_MODE};
+
+/// Version of the JSON export layout, bumped on incompatible changes.
+const JSON_EXPORT_VERSION: u32 = 1;
+
+/// Styles embedded in HTML exports so the file needs nothing else to display.
+const HTML_STYLE: &str = "body{font-family:-apple-system,BlinkMacSystemFont,\"Segoe UI\",\"PingFang SC\",\"Microsoft YaHei\",sans-serif;max-width:760px;margin:2rem auto;padding:0 1rem;color:#1f2328;line-height:1.6}\
+header.meta{color:#656d76;font-size:.875rem;margin-bottom:1.5rem}\
+section.message{border-radius:8px;padding:.75rem 1rem;margin:1rem 0}\
+section.user{background:#eef4ff}\
+section.assistant{background:#f6f8fa}\
+section.error{background:#fff1f0;border:1px solid #ffccc7}\
+section.message header{font-size:.8125rem;color:#656d76;margin-bottom:.25rem}\
+section.message header strong{color:#1f2328;margin-right:.5rem}\
+.content{white-space:pre-wrap;word-wrap:break-word}";
+
+/// Formats a conversation can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Readable Markdown with roles and timestamps, for pasting into docs and tickets.
+    Markdown,
+    /// Every stored field, so the export can be read back without loss.
+    Json,
+    /// A single HTML file with its styles inlined.
+    Html,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+            ExportFormat::Html => "html",
+        }
+    }
+}
+
+/// A rendered conversation and the file name to save it under.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportedConversation {
+    pub file_name: String,
+    pub content: String,
+}
+
+/// Result of exporting all conversations into a zip archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportArchive {
+    pub path: String,
+    pub conversations: usize,
+}
+
+/// The JSON export layout: the conversation with a version marker.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonExport {
+    pub version: u32,
+    pub conversation: Conversation,
+}
+
+/// Renders conversation `id` from `sessions_dir` in `format`.
+pub fn export_conversation(
+    sessions_dir: &Path,
+    id: &str,
+    format: ExportFormat,
+) -> Result<ExportedConversation, EasyClawError> {
+    let conversation = conversations::load_conversation(sessions_dir, id)?;
+    Ok(ExportedConversation { file_name: entry_name(&conversation, format), content: render(&conversation, format)? })
+}
+
+/// Writes every conversation in `sessions_dir`, rendered in `format`, into a zip
+/// archive in `exports_dir`, named `file_name` or after the current time. An existing
+/// archive of that name is replaced atomically; any other file is left alone.
+pub fn export_all(
+    sessions_dir: &Path,
+    format: ExportFormat,
+    exports_dir: &Path,
+    file_name: Option<&str>,
+) -> Result<ExportArchive, EasyClawError> {
+    let destination = archive_path(exports_dir, file_name)?;
+    if destination.exists() && !is_zip_archive(&destination) {
+        return Err(EasyClawError::InvalidInput(format!(
+            "Refusing to replace {}: it is not a zip archive",
+            destination.display()
+        )));
+    }
+    let destination = destination.as_path();
+
+    let mut summaries = conversations::list_conversations(sessions_dir)?;
+    if summaries.is_empty() {
+        return Err(EasyClawError::NotFound("There are no conversations to export".to_string()));
+    }
+    summaries.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(zip_time(Local::now()));
+    let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+    for summary in &summaries {
+        let conversation = conversations::load_conversation(sessions_dir, &summary.id)?;
+        let content = render(&conversation, format)?;
+        let added = match archive.start_file(entry_name(&conversation, format), options) {
+            Ok(()) => archive.write_all(content.as_bytes()).map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        added.map_err(|e| EasyClawError::Internal(format!("Failed to add conversation {} to the archive: {}", summary.id, e)))?;
+    }
+    let bytes = archive
+        .finish()
+        .map_err(|e| EasyClawError::Internal(format!("Failed to finish the archive: {}", e)))?
+        .into_inner();
+
+    fs::create_dir_all(exports_dir)
+        .map_err(|e| EasyClawError::io(format!("Failed to create {}", exports_dir.display()), &e))?;
+    write_atomic(destination, &bytes, Some(PRIVATE_FILE_MODE))?;
+    info!("[export_all_conversations] Exported {} conversations to {}", summaries.len(), destination.display());
+
+    Ok(ExportArchive { path: destination.to_string_lossy().into_owned(), conversations: summaries.len() })
+}
+
+/// Renders `conversation` in `format`.
+pub fn render(conversation: &Conversation, format: ExportFormat) -> Result<String, EasyClawError> {
+    match format {
+        ExportFormat::Markdown => Ok(render_markdown(conversation)),
+        ExportFormat::Json => {
+            let export = JsonExport { version: JSON_EXPORT_VERSION, conversation: conversation.clone() };
+            serde_json::to_string_pretty(&export)
+                .map_err(|e| EasyClawError::Internal(format!("Failed to serialize conversation: {}", e)))
+        }
+        ExportFormat::Html => Ok(render_html(conversation)),
+    }
+}
+
+fn render_markdown(conversation: &Conversation) -> String {
+    let mut out = format!(
+        "# {}\n\n- Conversation: `{}`\n- Created: {}\n- Updated: {}\n",
+        conversation.title,
+        conversation.id,
+        display_time(&conversation.created_at),
+        display_time(&conversation.updated_at),
+    );
+    for message in &conversation.messages {
+        out.push_str(&format!("\n---\n\n### {}\n\n", message_heading(message)));
+        out.push_str(message.content.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn render_html(conversation: &Conversation) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{title}</title>\n<style>{style}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<header class=\"meta\">Created {created} · Updated {updated}</header>\n",
+        title = escape_html(&conversation.title),
+        style = HTML_STYLE,
+        created = escape_html(&display_time(&conversation.created_at)),
+        updated = escape_html(&display_time(&conversation.updated_at)),
+    );
+    for message in &conversation.messages {
+        let class = match (message.role, message.status) {
+            (_, MessageStatus::Error) => "assistant error",
+            (ChatRole::User, _) => "user",
+            (ChatRole::Assistant, _) => "assistant",
+        };
+        out.push_str(&format!(
+            "<section class=\"message {}\">\n<header><strong>{}</strong><time datetime=\"{}\">{}</time></header>\n<div class=\"content\">{}</div>\n</section>\n",
+            class,
+            role_label(message.role),
+            message_time(message.timestamp).map(|time| time.to_rfc3339()).unwrap_or_default(),
+            escape_html(&message_time_label(message.timestamp)),
+            escape_html(message.content.trim_end()),
+        ));
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// The archive path in `exports_dir`: `file_name`, which must be a plain `.zip` file
+/// name, or `conversations-<local time>.zip`.
+fn archive_path(exports_dir: &Path, file_name: Option<&str>) -> Result<PathBuf, EasyClawError> {
+    let Some(name) = file_name else {
+        return Ok(exports_dir.join(format!("conversations-{}.zip", Local::now().format("%Y%m%d-%H%M%S"))));
+    };
+    let stem = name
+        .strip_suffix(".zip")
+        .or_else(|| name.strip_suffix(".ZIP"))
+        .unwrap_or_default();
+    let plain = !stem.is_empty() && !stem.starts_with('.') && !name.contains(['/', '\\', ':']);
+    if !plain {
+        return Err(EasyClawError::InvalidInput(format!(
+            "Invalid archive name '{}': use a file name ending in .zip, without folders",
+            name
+        )));
+    }
+    Ok(exports_dir.join(name))
+}
+
+/// Whether `path` starts with a zip local-file or empty-archive signature.
+fn is_zip_archive(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|()| magic == *b"PK\x03\x04" || magic == *b"PK\x05\x06")
+}
+
+/// `chat-<id>.<ext>`, matching the name of the stored conversation file.
+fn entry_name(conversation: &Conversation, format: ExportFormat) -> String {
+    format!("chat-{}.{}", conversation.id, format.extension())
+}
+
+fn message_heading(message: &StoredMessage) -> String {
+    let mut heading = format!("{} · {}", role_label(message.role), message_time_label(message.timestamp));
+    if message.status == MessageStatus::Error {
+        heading.push_str(" · error");
+    }
+    heading
+}
+
+fn role_label(role: ChatRole) -> &'static str {
+    match role {
+        ChatRole::User => "User",
+        ChatRole::Assistant => "Assistant",
+    }
+}
+
+fn message_time(timestamp: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(timestamp)
+}
+
+fn message_time_label(timestamp: i64) -> String {
+    message_time(timestamp).map(format_time).unwrap_or_else(|| timestamp.to_string())
+}
+
+/// An RFC 3339 time from the store in the format used by exports; kept as is if it
+/// doesn't parse.
+fn display_time(rfc3339: &str) -> String {
+    DateTime::parse_from_rfc3339(rfc3339)
+        .map(|time| format_time(time.with_timezone(&Utc)))
+        .unwrap_or_else(|_| rfc3339.to_string())
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+/// Zip entries store local time without a zone; falls back to the format's epoch (1980)
+/// for times it can't represent.
+fn zip_time(time: DateTime<Local>) -> zip::DateTime {
+    u16::try_from(time.year())
+        .ok()
+        .and_then(|year| {
+            zip::DateTime::from_date_and_time(
+                year,
+                time.month() as u8,
+                time.day() as u8,
+                time.hour() as u8,
+                time.minute() as u8,
+                time.second() as u8,
+            )
+            .ok()
+        })
+        .unwrap_or_default()
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn message(id: &str, role: ChatRole, content: &str, status: MessageStatus) -> StoredMessage {
+        // 2023-11-14 22:13:20 UTC
+        StoredMessage { id: id.into(), role, content: content.into(), timestamp: 1_700_000_000_000, status }
+    }
+
+    fn store(dir: &Path) {
+        conversations::append_message(dir, "c1", message("m1", ChatRole::User, "Deploy <script>?", MessageStatus::Done)).unwrap();
+        conversations::append_message(dir, "c1", message("m2", ChatRole::Assistant, "Run `deploy.sh`\n\n- then check logs\n", MessageStatus::Done)).unwrap();
+        conversations::append_message(dir, "c1", message("m3", ChatRole::Assistant, "Gateway returned HTTP 500", MessageStatus::Error)).unwrap();
+    }
+
+    #[test]
+    fn test_markdown_has_roles_and_timestamps() {
+        let tmp = TempDir::new().unwrap();
+        store(tmp.path());
+
+        let exported = export_conversation(tmp.path(), "c1", ExportFormat::Markdown).unwrap();
+        assert_eq!(exported.file_name, "chat-c1.md");
+        let body = exported.content.split_once("\n---\n").unwrap().1;
+        assert_eq!(
+            body,
+            "\n### User · 2023-11-14 22:13:20 UTC\n\nDeploy <script>?\n\
+             \n---\n\n### Assistant · 2023-11-14 22:13:20 UTC\n\nRun `deploy.sh`\n\n- then check logs\n\
+             \n---\n\n### Assistant · 2023-11-14 22:13:20 UTC · error\n\nGateway returned HTTP 500\n"
+        );
+        assert!(exported.content.starts_with("# Deploy <script>?\n\n- Conversation: `c1`\n"));
+    }
+
+    #[test]
+    fn test_json_is_lossless_and_html_is_escaped() {
+        let tmp = TempDir::new().unwrap();
+        store(tmp.path());
+        let conversation = conversations::load_conversation(tmp.path(), "c1").unwrap();
+
+        let json = export_conversation(tmp.path(), "c1", ExportFormat::Json).unwrap().content;
+        let parsed: JsonExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.version, JSON_EXPORT_VERSION);
+        assert_eq!(parsed.conversation, conversation);
+
+        let html = export_conversation(tmp.path(), "c1", ExportFormat::Html).unwrap().content;
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>"));
+        assert!(html.contains("<title>Deploy &lt;script&gt;?</title>"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("<section class=\"message assistant error\">"));
+        assert!(html.contains("<time datetime=\"2023-11-14T22:13:20+00:00\">2023-11-14 22:13:20 UTC</time>"));
+    }
+
+    #[test]
+    fn test_export_all_writes_zip() {
+        let tmp = TempDir::new().unwrap();
+        let sessions = tmp.path().join("sessions");
+        let exports = tmp.path().join("exports");
+        let destination = exports.join("all.zip");
+        assert_eq!(
+            export_all(&sessions, ExportFormat::Markdown, &exports, Some("all.zip")).unwrap_err().code(),
+            "not_found"
+        );
+
+        store(&sessions);
+        conversations::append_message(&sessions, "c2", message("m1", ChatRole::User, "second", MessageStatus::Done)).unwrap();
+
+        let archive = export_all(&sessions, ExportFormat::Markdown, &exports, Some("all.zip")).unwrap();
+        assert_eq!(archive.conversations, 2);
+        assert_eq!(archive.path, destination.to_string_lossy());
+        // Exporting again replaces the earlier archive
+        export_all(&sessions, ExportFormat::Markdown, &exports, Some("all.zip")).unwrap();
+
+        let mut zip = zip::ZipArchive::new(fs::File::open(&destination).unwrap()).unwrap();
+        let mut names: Vec<String> = zip.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(names, vec!["chat-c1.md", "chat-c2.md"]);
+        let mut content = String::new();
+        zip.by_name("chat-c2.md").unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!(content, render(&conversations::load_conversation(&sessions, "c2").unwrap(), ExportFormat::Markdown).unwrap());
+    }
+
+    #[test]
+    fn test_export_all_stays_in_exports_dir() {
+        let tmp = TempDir::new().unwrap();
+        let sessions = tmp.path().join("sessions");
+        let exports = tmp.path().join("exports");
+        store(&sessions);
+
+        for name in ["../escape.zip", "/tmp/abs.zip", "sub\\a.zip", ".zip", ".bashrc", "notes.txt"] {
+            let err = export_all(&sessions, ExportFormat::Json, &exports, Some(name)).unwrap_err();
+            assert_eq!(err.code(), "invalid_input", "{}", name);
+        }
+
+        fs::create_dir_all(&exports).unwrap();
+        fs::write(exports.join("keep.zip"), "not an archive").unwrap();
+        let err = export_all(&sessions, ExportFormat::Json, &exports, Some("keep.zip")).unwrap_err();
+        assert_eq!(err.code(), "invalid_input");
+        assert_eq!(fs::read_to_string(exports.join("keep.zip")).unwrap(), "not an archive");
+
+        let archive = export_all(&sessions, ExportFormat::Json, &exports, None).unwrap();
+        assert!(Path::new(&archive.path).starts_with(&exports));
+        assert!(archive.path.ends_with(".zip"));
+    }
+}
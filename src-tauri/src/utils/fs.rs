@@ -1,48 +1,215 @@
-use std::fs;
-use std::path::Path;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::utils::checksum::BinaryDigest;
+use crate::utils::error::EasyClawError;
+
+/// Permission bits for private files such as config.toml and its backups.
+pub const PRIVATE_FILE_MODE: u32 = 0o600;
+
+/// Permission bits for installed executables.
+pub const EXECUTABLE_MODE: u32 = 0o755;
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 /// Installs a binary file from `src` to `dst`.
 /// - Creates parent directories if needed
-/// - Only copies if `dst` does not already exist (idempotent)
-/// - On Unix, sets executable permission (chmod +x)
-/// Returns true if the binary was installed, false if it already existed.
-pub fn install_binary(src: &Path, dst: &Path) -> Result<bool, String> {
-    if dst.exists() {
-        return Ok(false);
+/// - Verifies `src` against `expected` before copying and the copy before it replaces `dst`,
+///   so a tampered or truncated binary is never installed
+/// - Atomically replaces an existing `dst`; deciding whether to replace it is up to the
+///   caller (see `installer::install_zeroclaw`)
+/// - On Unix, sets executable permission (chmod +x) before the binary appears at `dst`
+pub fn install_binary(src: &Path, dst: &Path, expected: &BinaryDigest) -> Result<(), EasyClawError> {
+    expected.verify(src)?;
+
+    let mut source = File::open(src)
+        .map_err(|e| EasyClawError::io(format!("Failed to open binary {}", src.display()), &e))?;
+
+    // Keeps the typed error of a failed check on the copy
+    let mut rejected = None;
+    atomic_replace_checked(
+        dst,
+        Some(EXECUTABLE_MODE),
+        |file| io::copy(&mut source, file).map(|_| ()),
+        |copy| {
+            expected.verify(copy).map_err(|e| {
+                let io_error = io::Error::other(e.to_string());
+                rejected = Some(e);
+                io_error
+            })
+        },
+    )
+    .map_err(|e| {
+        rejected.take().unwrap_or_else(|| {
+            EasyClawError::io(format!("Failed to install binary {} -> {}", src.display(), dst.display()), &e)
+        })
+    })?;
+
+    Ok(())
+}
+
+/// Atomically replaces `path` with `contents`.
+///
+/// The data is written to a temporary file in the same directory, flushed to disk,
+/// given its final permissions and only then renamed over `path`, so readers never
+/// see a truncated file and private files are never briefly world-readable.
+/// When `mode` is None an existing file keeps its permissions. `mode` is ignored on
+/// non-Unix platforms.
+pub fn write_atomic(path: &Path, contents: &[u8], mode: Option<u32>) -> Result<(), EasyClawError> {
+    atomic_replace(path, mode, |file| file.write_all(contents))
+        .map_err(|e| EasyClawError::io(format!("Failed to write {}", path.display()), &e))
+}
+
+/// Appends `lines` (newline-terminated) to `path` and flushes them to disk, creating
+/// the file with `mode` if needed. If the file doesn't end with a newline because an
+/// earlier append was cut short, a newline is written first so the partial line stays
+/// on its own and the new lines remain readable.
+pub fn append_lines(path: &Path, lines: &str, mode: Option<u32>) -> Result<(), EasyClawError> {
+    append(path, lines, mode).map_err(|e| EasyClawError::io(format!("Failed to append to {}", path.display()), &e))
+}
+
+fn append(path: &Path, lines: &str, mode: Option<u32>) -> io::Result<()> {
+    let mut file = open_append(path, mode)?;
+    let len = file.metadata()?.len();
+    let mut contents = String::with_capacity(lines.len() + 1);
+    if len > 0 {
+        file.seek(SeekFrom::Start(len - 1))?;
+        let mut last = [0u8; 1];
+        file.read_exact(&mut last)?;
+        if last[0] != b'\n' {
+            contents.push('\n');
+        }
     }
+    contents.push_str(lines);
+    // Append mode: writes always go to the end, wherever the read left the cursor
+    file.write_all(contents.as_bytes())?;
+    file.sync_data()
+}
+
+#[cfg(unix)]
+fn open_append(path: &Path, mode: Option<u32>) -> io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .mode(mode.unwrap_or(0o644))
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn open_append(path: &Path, _mode: Option<u32>) -> io::Result<File> {
+    OpenOptions::new().read(true).append(true).create(true).open(path)
+}
+
+fn atomic_replace(
+    path: &Path,
+    mode: Option<u32>,
+    write: impl FnOnce(&mut File) -> io::Result<()>,
+) -> io::Result<()> {
+    atomic_replace_checked(path, mode, write, |_| Ok(()))
+}
+
+/// Like `atomic_replace`, but runs `check` on the fully written temp file and keeps the
+/// original if it fails.
+fn atomic_replace_checked(
+    path: &Path,
+    mode: Option<u32>,
+    write: impl FnOnce(&mut File) -> io::Result<()>,
+    check: impl FnOnce(&Path) -> io::Result<()>,
+) -> io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(parent)?;
+
+    let mode = mode.or_else(|| existing_mode(path));
+    let tmp_path = temp_path_for(path);
 
-    if let Some(parent) = dst.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create bin dir {}: {}", parent.display(), e))?;
+    let result = (|| {
+        let mut file = create_temp(&tmp_path, mode)?;
+        write(&mut file)?;
+        file.sync_all()?;
+        drop(file);
+        check(&tmp_path)?;
+        fs::rename(&tmp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
     }
+    result?;
 
-    fs::copy(src, dst).map_err(|e| {
-        format!(
-            "Failed to install binary {} -> {}: {}",
-            src.display(),
-            dst.display(),
-            e
-        )
-    })?;
+    sync_dir(parent);
+    Ok(())
+}
 
-    // Set executable permission on Unix
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let perms = fs::Permissions::from_mode(0o755);
-        fs::set_permissions(dst, perms).map_err(|e| {
-            format!("Failed to set executable permission on {}: {}", dst.display(), e)
-        })?;
+/// Builds a unique hidden temp path next to `path`, e.g. `.config.toml.1234.0.tmp`.
+fn temp_path_for(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{}.{}.{}.tmp", name, std::process::id(), counter))
+}
+
+#[cfg(unix)]
+fn create_temp(tmp_path: &Path, mode: Option<u32>) -> io::Result<File> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(mode.unwrap_or(0o644))
+        .open(tmp_path)?;
+    // The open mode is filtered by the umask; set the exact bits explicitly
+    if let Some(mode) = mode {
+        file.set_permissions(fs::Permissions::from_mode(mode))?;
     }
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+fn create_temp(tmp_path: &Path, _mode: Option<u32>) -> io::Result<File> {
+    OpenOptions::new().write(true).create_new(true).open(tmp_path)
+}
 
-    Ok(true)
+#[cfg(unix)]
+fn existing_mode(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).ok().map(|m| m.permissions().mode() & 0o7777)
 }
 
+#[cfg(not(unix))]
+fn existing_mode(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// Flushes the directory entry so the rename survives a crash (best effort).
+#[cfg(unix)]
+fn sync_dir(dir: &Path) {
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    fn digest(path: &Path) -> BinaryDigest {
+        BinaryDigest::of_file(path).unwrap()
+    }
+
     #[test]
     fn test_install_binary_copies_and_sets_executable() {
         let tmp = TempDir::new().unwrap();
@@ -51,8 +218,7 @@ mod tests {
 
         fs::write(&src, "#!/bin/sh\necho hello").unwrap();
 
-        let installed = install_binary(&src, &dst).unwrap();
-        assert!(installed);
+        install_binary(&src, &dst, &digest(&src)).unwrap();
         assert!(dst.exists());
 
         #[cfg(unix)]
@@ -64,21 +230,114 @@ mod tests {
     }
 
     #[test]
-    fn test_install_binary_is_idempotent() {
+    fn test_install_binary_replaces_existing() {
         let tmp = TempDir::new().unwrap();
         let src = tmp.path().join("my_bin");
         let dst = tmp.path().join("my_bin_installed");
 
         fs::write(&src, "binary v1").unwrap();
-
-        let first = install_binary(&src, &dst).unwrap();
-        assert!(first);
+        install_binary(&src, &dst, &digest(&src)).unwrap();
 
         fs::write(&src, "binary v2").unwrap();
+        install_binary(&src, &dst, &digest(&src)).unwrap();
+
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "binary v2");
+    }
+
+    #[test]
+    fn test_install_binary_refuses_tampered_source() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("my_bin");
+        let dst = tmp.path().join("my_bin_installed");
+        fs::write(&dst, "installed").unwrap();
+
+        fs::write(&src, "genuine").unwrap();
+        let expected = digest(&src);
+        fs::write(&src, "tampered").unwrap();
+
+        let err = install_binary(&src, &dst, &expected).unwrap_err();
+        assert_eq!(err.code(), "integrity_check_failed", "{}", err);
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "installed");
+    }
+
+    #[test]
+    fn test_install_binary_refuses_corrupted_copy() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("my_bin");
+        fs::write(&path, "installed").unwrap();
+        let expected = BinaryDigest { sha256: "00".repeat(32), size: 3 };
+
+        let result = atomic_replace_checked(
+            &path,
+            None,
+            |file| file.write_all(b"bad"),
+            |copy| expected.verify(copy).map_err(io::Error::other),
+        );
+
+        assert!(result.unwrap_err().to_string().contains("SHA-256"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "installed");
+        assert_eq!(dir_entries(tmp.path()), vec!["my_bin"]);
+    }
+
+    fn dir_entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_write_atomic_creates_and_replaces_without_leftovers() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("nested/config.toml");
+
+        write_atomic(&path, b"port = 1\n", None).unwrap();
+        write_atomic(&path, b"port = 2\n", None).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "port = 2\n");
+        assert_eq!(dir_entries(&tmp.path().join("nested")), vec!["config.toml"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_sets_and_preserves_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+
+        write_atomic(&path, b"secret", Some(PRIVATE_FILE_MODE)).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+        // Without an explicit mode the existing permissions are kept
+        write_atomic(&path, b"secret v2", None).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_write_atomic_failure_leaves_original_intact() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "original").unwrap();
+
+        let result = atomic_replace(&path, None, |_| Err(io::Error::other("disk full")));
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+        assert_eq!(dir_entries(tmp.path()), vec!["config.toml"]);
+    }
+
+    #[test]
+    fn test_append_lines_terminates_a_partial_last_line() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("chat.jsonl");
 
-        let second = install_binary(&src, &dst).unwrap();
-        assert!(!second);
+        append_lines(&path, "one\n", None).unwrap();
+        fs::write(&path, "one\ntw").unwrap();
+        append_lines(&path, "three\n", None).unwrap();
 
-        assert_eq!(fs::read_to_string(&dst).unwrap(), "binary v1");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntw\nthree\n");
     }
 }
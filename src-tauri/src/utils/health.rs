@@ -0,0 +1,234 @@
+use std::time::{Duration, Instant};
+
+use serde_json::Value as JsonValue;
+
+use crate::utils::net::GatewayEndpoint;
+
+/// How long a single health probe may take before the gateway counts as unreachable.
+pub const HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Delay between probes while waiting for the gateway to come up.
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Result of probing the gateway's `/health` endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HealthReport {
+    /// True if `/health` answered with a 2xx status.
+    pub reachable: bool,
+    /// Daemon PID, if the health payload reports one.
+    pub pid: Option<u32>,
+    /// Daemon uptime in seconds, if the health payload reports it.
+    pub uptime_secs: Option<u64>,
+    /// Why the probe failed, if it did.
+    pub error: Option<String>,
+}
+
+/// Sends `GET {url}/health` to the gateway and summarizes the answer.
+/// Never fails: connection errors and bad statuses are reported in the result.
+pub async fn probe_health(endpoint: &GatewayEndpoint, timeout: Duration) -> HealthReport {
+    let client = match reqwest::Client::builder().timeout(timeout).no_proxy().build() {
+        Ok(client) => client,
+        Err(e) => return unreachable(format!("Failed to create HTTP client: {}", e)),
+    };
+
+    let response = match client.get(format!("{}/health", endpoint.url)).send().await {
+        Ok(response) => response,
+        Err(e) => return unreachable(format!("Gateway not reachable at {}: {}", endpoint.url, e)),
+    };
+
+    let status = response.status();
+    if !status.is_success() {
+        return unreachable(format!("Gateway health check returned HTTP {}", status.as_u16()));
+    }
+
+    let body = response.json::<JsonValue>().await.unwrap_or(JsonValue::Null);
+    HealthReport {
+        reachable: true,
+        pid: find_number(&body, "pid").and_then(|pid| u32::try_from(pid).ok()),
+        uptime_secs: find_number(&body, "uptime_seconds").or_else(|| find_number(&body, "uptime")),
+        error: None,
+    }
+}
+
+/// Polls `/health` until the gateway answers or `timeout` elapses.
+pub async fn wait_until_healthy(endpoint: &GatewayEndpoint, timeout: Duration) -> Result<HealthReport, String> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let report = probe_health(endpoint, HEALTH_TIMEOUT.min(timeout)).await;
+        if report.reachable {
+            return Ok(report);
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "Gateway did not become healthy within {}s: {}",
+                timeout.as_secs(),
+                report.error.unwrap_or_default()
+            ));
+        }
+        tokio::time::sleep(HEALTH_POLL_INTERVAL).await;
+    }
+}
+
+/// Polls `/health` after a restart until the new gateway process answers or `timeout`
+/// elapses. `before` is a probe taken before the restart: answers of the old process
+/// (see `is_replacement`) don't count until the gateway was seen down.
+pub async fn wait_until_restarted(
+    endpoint: &GatewayEndpoint,
+    before: &HealthReport,
+    timeout: Duration,
+) -> Result<HealthReport, String> {
+    if !before.reachable {
+        return wait_until_healthy(endpoint, timeout).await;
+    }
+    let deadline = Instant::now() + timeout;
+    let mut went_down = false;
+    loop {
+        let report = probe_health(endpoint, HEALTH_TIMEOUT.min(timeout)).await;
+        if report.reachable && (went_down || is_replacement(before, &report)) {
+            return Ok(report);
+        }
+        went_down |= !report.reachable;
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "Gateway did not come back within {}s: {}",
+                timeout.as_secs(),
+                report.error.unwrap_or_else(|| "the old process is still answering".to_string())
+            ));
+        }
+        tokio::time::sleep(HEALTH_POLL_INTERVAL).await;
+    }
+}
+
+/// Whether `report` comes from another process than `before`: the PID differs or the
+/// uptime started over. Without either in the payload the processes can't be told apart,
+/// so any answer counts.
+fn is_replacement(before: &HealthReport, report: &HealthReport) -> bool {
+    if let (Some(old), Some(new)) = (before.pid, report.pid) {
+        return old != new;
+    }
+    match (before.uptime_secs, report.uptime_secs) {
+        (Some(old), Some(new)) => new < old,
+        _ => true,
+    }
+}
+
+fn unreachable(error: String) -> HealthReport {
+    HealthReport {
+        error: Some(error),
+        ..HealthReport::default()
+    }
+}
+
+/// Looks up a numeric field at the top level of the health payload or inside its
+/// `runtime` object, where ZeroClaw reports process details.
+fn find_number(body: &JsonValue, key: &str) -> Option<u64> {
+    body.get(key)
+        .or_else(|| body.get("runtime").and_then(|runtime| runtime.get(key)))
+        .and_then(JsonValue::as_u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serves a single canned HTTP response on a random local port.
+    fn serve_once(status_line: &'static str, body: &'static str) -> GatewayEndpoint {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+            let response = format!(
+                "{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status_line,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        GatewayEndpoint::new("127.0.0.1", port)
+    }
+
+    #[tokio::test]
+    async fn test_probe_health_reads_runtime_details() {
+        let endpoint = serve_once(
+            "HTTP/1.1 200 OK",
+            r#"{"status":"ok","runtime":{"pid":4242,"uptime_seconds":90}}"#,
+        );
+
+        let report = probe_health(&endpoint, HEALTH_TIMEOUT).await;
+
+        assert_eq!(
+            report,
+            HealthReport { reachable: true, pid: Some(4242), uptime_secs: Some(90), error: None }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_health_reports_http_errors() {
+        let endpoint = serve_once("HTTP/1.1 503 Service Unavailable", "{}");
+
+        let report = probe_health(&endpoint, HEALTH_TIMEOUT).await;
+
+        assert!(!report.reachable);
+        assert!(report.error.unwrap().contains("503"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_health_reports_connection_refused() {
+        let port = TcpListener::bind(("127.0.0.1", 0)).unwrap().local_addr().unwrap().port();
+
+        let report = probe_health(&GatewayEndpoint::new("127.0.0.1", port), HEALTH_TIMEOUT).await;
+
+        assert!(!report.reachable);
+        assert!(report.error.unwrap().contains("not reachable"));
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_healthy_returns_once_reachable() {
+        let endpoint = serve_once("HTTP/1.1 200 OK", r#"{"status":"ok"}"#);
+
+        let report = wait_until_healthy(&endpoint, Duration::from_secs(5)).await.unwrap();
+
+        assert!(report.reachable);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_healthy_times_out() {
+        let port = TcpListener::bind(("127.0.0.1", 0)).unwrap().local_addr().unwrap().port();
+
+        let err = wait_until_healthy(&GatewayEndpoint::new("127.0.0.1", port), Duration::from_millis(600))
+            .await
+            .unwrap_err();
+
+        assert!(err.contains("did not become healthy"));
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_restarted_ignores_the_old_process() {
+        let before = HealthReport { reachable: true, pid: Some(4242), uptime_secs: Some(90), error: None };
+
+        let endpoint = serve_once("HTTP/1.1 200 OK", r#"{"runtime":{"pid":4242,"uptime_seconds":91}}"#);
+        let err = wait_until_restarted(&endpoint, &before, Duration::from_millis(600)).await.unwrap_err();
+        assert!(err.contains("did not come back"));
+
+        let endpoint = serve_once("HTTP/1.1 200 OK", r#"{"runtime":{"pid":5151,"uptime_seconds":1}}"#);
+        let report = wait_until_restarted(&endpoint, &before, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(report.pid, Some(5151));
+    }
+
+    #[test]
+    fn test_is_replacement_compares_pid_then_uptime() {
+        let report = |pid, uptime_secs| HealthReport { reachable: true, pid, uptime_secs, error: None };
+
+        assert!(!is_replacement(&report(Some(1), Some(90)), &report(Some(1), Some(1))));
+        assert!(is_replacement(&report(Some(1), None), &report(Some(2), None)));
+        assert!(!is_replacement(&report(None, Some(90)), &report(None, Some(95))));
+        assert!(is_replacement(&report(None, Some(90)), &report(None, Some(2))));
+        assert!(is_replacement(&report(None, None), &report(None, None)));
+    }
+}
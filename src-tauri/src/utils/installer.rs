@@ -0,0 +1,401 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::utils::checksum::BinaryDigest;
+use crate::utils::error::EasyClawError;
+use crate::utils::fs::{install_binary, write_atomic};
+use crate::utils::process::ProcessRunner;
+use crate::utils::version::{compare_versions, query_version};
+
+/// Installed ZeroClaw versions kept under `bin/versions/`, including the active one.
+pub const KEPT_VERSIONS: usize = 3;
+
+/// Directory next to the active binary that holds one subdirectory per installed version.
+const VERSIONS_DIR: &str = "versions";
+
+/// File in `bin/versions/` naming the active version.
+const ACTIVE_FILE: &str = "active";
+
+/// Store label for a binary that doesn't report a version.
+const UNKNOWN_VERSION: &str = "unknown";
+
+/// What `install_zeroclaw` did with the bundled binary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum InstallOutcome {
+    /// Nothing was installed before.
+    Installed { version: Option<String> },
+    /// The bundled binary was newer; the old one stays in `bin/versions/` for rollback.
+    Upgraded { from: Option<String>, to: String },
+    /// The active binary was kept: it is as new as the bundled one, or the bundled
+    /// version is already installed (e.g. the user rolled back from it).
+    UpToDate { installed: Option<String>, bundled: Option<String> },
+}
+
+/// An entry of `list_zeroclaw_versions`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstalledVersion {
+    pub version: String,
+    pub active: bool,
+}
+
+/// ZeroClaw versions installed side by side under `bin/versions/<version>/`.
+///
+/// The active version is copied to the binary everything runs (`bin/zeroclaw`) and
+/// recorded in `bin/versions/active`; copying rather than linking works on every OS.
+#[derive(Debug, Clone)]
+pub struct VersionStore {
+    bin_path: PathBuf,
+    dir: PathBuf,
+}
+
+impl VersionStore {
+    /// Store for the active binary at `bin_path`, e.g. `~/.zeroclaw/bin/zeroclaw`.
+    pub fn for_binary(bin_path: &Path) -> Self {
+        let dir = bin_path.parent().unwrap_or(Path::new(".")).join(VERSIONS_DIR);
+        Self { bin_path: bin_path.to_path_buf(), dir }
+    }
+
+    /// The active version, if one was recorded.
+    pub fn active(&self) -> Option<String> {
+        fs::read_to_string(self.dir.join(ACTIVE_FILE))
+            .ok()
+            .map(|version| version.trim().to_string())
+            .filter(|version| !version.is_empty())
+    }
+
+    pub fn contains(&self, version: &str) -> bool {
+        is_valid_label(version) && self.binary_path(version).is_file()
+    }
+
+    /// Installed versions, newest first.
+    pub fn list(&self) -> Result<Vec<InstalledVersion>, EasyClawError> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(EasyClawError::io(format!("Failed to read {}", self.dir.display()), &e)),
+        };
+
+        let active = self.active();
+        let mut versions: Vec<InstalledVersion> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| self.contains(name))
+            .map(|version| InstalledVersion { active: active.as_deref() == Some(version.as_str()), version })
+            .collect();
+        versions.sort_by(|a, b| compare_versions(&b.version, &a.version));
+        Ok(versions)
+    }
+
+    /// Makes `version` the active binary. The copy is checked against the stored binary
+    /// before it replaces the active one.
+    pub fn activate(&self, version: &str) -> Result<(), EasyClawError> {
+        if !self.contains(version) {
+            return Err(EasyClawError::NotFound(format!("ZeroClaw version {} is not installed", version)));
+        }
+        let stored = self.binary_path(version);
+        install_binary(&stored, &self.bin_path, &BinaryDigest::of_file(&stored)?)?;
+        write_atomic(&self.dir.join(ACTIVE_FILE), version.as_bytes(), None)?;
+        info!("[VersionStore::activate] Active ZeroClaw version is now {}", version);
+        Ok(())
+    }
+
+    /// Moves the binary at `path` into the store as `version`.
+    fn add(&self, path: &Path, version: &str) -> Result<(), EasyClawError> {
+        let target = self.binary_path(version);
+        let version_dir = self.dir.join(version);
+        fs::create_dir_all(&version_dir)
+            .map_err(|e| EasyClawError::io(format!("Failed to create {}", version_dir.display()), &e))?;
+        // rename() doesn't replace an existing file on every platform
+        let _ = fs::remove_file(&target);
+        fs::rename(path, &target)
+            .map_err(|e| EasyClawError::io(format!("Failed to move {} to {}", path.display(), target.display()), &e))
+    }
+
+    /// Records a binary installed before versions were tracked, so it can be rolled back to.
+    async fn adopt_active_binary(&self, runner: &dyn ProcessRunner) -> Result<Option<String>, EasyClawError> {
+        let version = query_version(runner, &self.bin_path).await;
+        let label = version_label(version.as_deref());
+        let copy = sibling_path(&self.bin_path, "adopt");
+        install_binary(&self.bin_path, &copy, &BinaryDigest::of_file(&self.bin_path)?)?;
+        self.add(&copy, &label)?;
+        write_atomic(&self.dir.join(ACTIVE_FILE), label.as_bytes(), None)?;
+        info!("[VersionStore::adopt_active_binary] Recorded existing zeroclaw as version {}", label);
+        Ok(version)
+    }
+
+    /// Removes the oldest versions beyond `KEPT_VERSIONS`. The active one is always kept.
+    fn prune(&self) -> Result<(), EasyClawError> {
+        let versions = self.list()?;
+        let mut kept = versions.iter().filter(|v| v.active).count();
+        for entry in versions.iter().filter(|v| !v.active) {
+            if kept < KEPT_VERSIONS {
+                kept += 1;
+                continue;
+            }
+            let version_dir = self.dir.join(&entry.version);
+            match fs::remove_dir_all(&version_dir) {
+                Ok(()) => info!("[VersionStore::prune] Removed ZeroClaw {}", entry.version),
+                Err(e) => warn!("[VersionStore::prune] Failed to remove {}: {}", version_dir.display(), e),
+            }
+        }
+        Ok(())
+    }
+
+    fn binary_path(&self, version: &str) -> PathBuf {
+        let name = self.bin_path.file_name().unwrap_or_default();
+        self.dir.join(version).join(name)
+    }
+}
+
+/// Installs the bundled ZeroClaw binary `src` at `dst`, or upgrades `dst` if the bundled
+/// one reports a newer `--version` that isn't installed yet. Every installed version is
+/// kept in the store next to `dst` for rollback (see `VersionStore`).
+/// `src` must match `expected`, the digest recorded when the app was built. Versions are
+/// queried through `runner`.
+pub async fn install_zeroclaw(
+    runner: &dyn ProcessRunner,
+    src: &Path,
+    dst: &Path,
+    expected: &BinaryDigest,
+) -> Result<InstallOutcome, EasyClawError> {
+    let store = VersionStore::for_binary(dst);
+
+    // Stage the bundled binary next to the installed one, so it can be run to read its
+    // version regardless of the permissions it was packaged with
+    let staged = sibling_path(dst, "new");
+    install_binary(src, &staged, expected)?;
+    let bundled = query_version(runner, &staged).await;
+
+    if !dst.exists() {
+        let label = version_label(bundled.as_deref());
+        store.add(&staged, &label)?;
+        store.activate(&label)?;
+        info!("[install_zeroclaw] Installed zeroclaw {:?} at {:?}", bundled, dst);
+        return Ok(InstallOutcome::Installed { version: bundled });
+    }
+
+    let installed = match store.active() {
+        Some(active) => Some(active).filter(|version| version != UNKNOWN_VERSION),
+        None => store.adopt_active_binary(runner).await?,
+    };
+
+    let already_stored = bundled.as_deref().is_some_and(|version| store.contains(version));
+    let upgrade = should_upgrade(installed.as_deref(), bundled.as_deref()) && !already_stored;
+    let (true, Some(to)) = (upgrade, bundled.clone()) else {
+        let _ = fs::remove_file(&staged);
+        info!("[install_zeroclaw] Keeping zeroclaw {:?} (bundled: {:?})", installed, bundled);
+        return Ok(InstallOutcome::UpToDate { installed, bundled });
+    };
+
+    store.add(&staged, &to)?;
+    store.activate(&to)?;
+    store.prune()?;
+
+    info!("[install_zeroclaw] Upgraded zeroclaw {:?} -> {}", installed, to);
+    Ok(InstallOutcome::Upgraded { from: installed, to })
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    path.with_file_name(format!("{}.{}", name, suffix))
+}
+
+/// Directory name for `version` in the store. Anything that isn't a plain version
+/// string is stored as `unknown`.
+fn version_label(version: Option<&str>) -> String {
+    version.filter(|version| is_valid_label(version)).unwrap_or(UNKNOWN_VERSION).to_string()
+}
+
+fn is_valid_label(version: &str) -> bool {
+    !version.is_empty()
+        && version != ACTIVE_FILE
+        && !version.starts_with('.')
+        && version.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_'))
+}
+
+fn should_upgrade(installed: Option<&str>, bundled: Option<&str>) -> bool {
+    match (installed, bundled) {
+        // Can't tell what we ship, so leave a working install alone
+        (_, None) => false,
+        // The installed binary doesn't run or predates `--version`
+        (None, Some(_)) => true,
+        (Some(installed), Some(bundled)) => compare_versions(bundled, installed) == Ordering::Greater,
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::utils::process::SystemRunner;
+    use tempfile::TempDir;
+
+    fn write_versioned_binary(path: &Path, version: &str) {
+        fs::write(path, format!("#!/bin/sh\necho \"zeroclaw {}\"\n", version)).unwrap();
+    }
+
+    fn digest(path: &Path) -> BinaryDigest {
+        BinaryDigest::of_file(path).unwrap()
+    }
+
+    fn installed_version(path: &Path) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    fn listed(store: &VersionStore) -> Vec<(String, bool)> {
+        store.list().unwrap().into_iter().map(|v| (v.version, v.active)).collect()
+    }
+
+    async fn install(src: &Path, dst: &Path, version: &str) -> InstallOutcome {
+        write_versioned_binary(src, version);
+        install_zeroclaw(&SystemRunner, src, dst, &digest(src)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_install_zeroclaw_fresh_install() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("bundled");
+        let dst = tmp.path().join("bin/zeroclaw");
+
+        let outcome = install(&src, &dst, "0.1.0").await;
+
+        assert_eq!(outcome, InstallOutcome::Installed { version: Some("0.1.0".into()) });
+        assert!(installed_version(&dst).contains("0.1.0"));
+        assert_eq!(listed(&VersionStore::for_binary(&dst)), vec![("0.1.0".into(), true)]);
+    }
+
+    #[tokio::test]
+    async fn test_install_zeroclaw_upgrades_and_keeps_previous() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("bundled");
+        let dst = tmp.path().join("bin/zeroclaw");
+        install(&src, &dst, "0.1.0").await;
+
+        let outcome = install(&src, &dst, "0.2.0").await;
+
+        assert_eq!(outcome, InstallOutcome::Upgraded { from: Some("0.1.0".into()), to: "0.2.0".into() });
+        assert!(installed_version(&dst).contains("0.2.0"));
+        let store = VersionStore::for_binary(&dst);
+        assert_eq!(listed(&store), vec![("0.2.0".into(), true), ("0.1.0".into(), false)]);
+        assert!(!sibling_path(&dst, "new").exists());
+    }
+
+    #[tokio::test]
+    async fn test_install_zeroclaw_keeps_same_or_newer_install() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("bundled");
+        let dst = tmp.path().join("bin/zeroclaw");
+        install(&src, &dst, "0.3.0").await;
+
+        let same = install(&src, &dst, "0.3.0").await;
+        let older = install(&src, &dst, "0.2.0").await;
+
+        assert_eq!(same, InstallOutcome::UpToDate { installed: Some("0.3.0".into()), bundled: Some("0.3.0".into()) });
+        assert_eq!(older, InstallOutcome::UpToDate { installed: Some("0.3.0".into()), bundled: Some("0.2.0".into()) });
+        assert!(installed_version(&dst).contains("0.3.0"));
+        assert_eq!(listed(&VersionStore::for_binary(&dst)).len(), 1);
+        assert!(!sibling_path(&dst, "new").exists());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_survives_next_sync() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("bundled");
+        let dst = tmp.path().join("bin/zeroclaw");
+        install(&src, &dst, "0.1.0").await;
+        install(&src, &dst, "0.2.0").await;
+        let store = VersionStore::for_binary(&dst);
+
+        store.activate("0.1.0").unwrap();
+        assert!(installed_version(&dst).contains("0.1.0"));
+
+        // The app still bundles 0.2.0, which the user rolled back from
+        let outcome = install(&src, &dst, "0.2.0").await;
+        assert_eq!(outcome, InstallOutcome::UpToDate { installed: Some("0.1.0".into()), bundled: Some("0.2.0".into()) });
+        assert!(installed_version(&dst).contains("0.1.0"));
+        assert_eq!(store.active().as_deref(), Some("0.1.0"));
+    }
+
+    #[tokio::test]
+    async fn test_install_zeroclaw_prunes_old_versions_but_not_active() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("bundled");
+        let dst = tmp.path().join("bin/zeroclaw");
+        install(&src, &dst, "0.1.0").await;
+        let store = VersionStore::for_binary(&dst);
+
+        for version in ["0.2.0", "0.3.0", "0.4.0"] {
+            install(&src, &dst, version).await;
+        }
+        let versions: Vec<String> = store.list().unwrap().into_iter().map(|v| v.version).collect();
+        assert_eq!(versions, vec!["0.4.0", "0.3.0", "0.2.0"]);
+
+        // With an older version active, the newest others fill the remaining slots
+        store.activate("0.2.0").unwrap();
+        write_versioned_binary(&tmp.path().join("extra"), "0.9.0");
+        store.add(&tmp.path().join("extra"), "0.9.0").unwrap();
+        store.prune().unwrap();
+        assert_eq!(listed(&store), vec![
+            ("0.9.0".into(), false),
+            ("0.4.0".into(), false),
+            ("0.2.0".into(), true),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_install_zeroclaw_adopts_untracked_install() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("bundled");
+        let dst = tmp.path().join("bin/zeroclaw");
+        fs::create_dir_all(dst.parent().unwrap()).unwrap();
+        write_versioned_binary(&dst, "0.1.0");
+        fs::set_permissions(&dst, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+
+        let outcome = install(&src, &dst, "0.2.0").await;
+
+        assert_eq!(outcome, InstallOutcome::Upgraded { from: Some("0.1.0".into()), to: "0.2.0".into() });
+        assert_eq!(listed(&VersionStore::for_binary(&dst)), vec![("0.2.0".into(), true), ("0.1.0".into(), false)]);
+    }
+
+    #[tokio::test]
+    async fn test_install_zeroclaw_rejects_tampered_upgrade() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("bundled");
+        let dst = tmp.path().join("bin/zeroclaw");
+        install(&src, &dst, "0.1.0").await;
+
+        write_versioned_binary(&src, "0.2.0");
+        let expected = digest(&src);
+        write_versioned_binary(&src, "6.6.6");
+
+        assert!(install_zeroclaw(&SystemRunner, &src, &dst, &expected).await.is_err());
+        assert!(installed_version(&dst).contains("0.1.0"));
+        assert!(!sibling_path(&dst, "new").exists());
+    }
+
+    #[tokio::test]
+    async fn test_activate_rejects_unknown_or_unsafe_versions() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("bundled");
+        let dst = tmp.path().join("bin/zeroclaw");
+        install(&src, &dst, "0.1.0").await;
+        let store = VersionStore::for_binary(&dst);
+
+        assert!(store.activate("0.9.0").unwrap_err().detail().contains("not installed"));
+        assert!(store.activate("../0.1.0").is_err());
+        assert!(store.activate("active").is_err());
+        assert_eq!(store.active().as_deref(), Some("0.1.0"));
+    }
+
+    #[test]
+    fn test_should_upgrade() {
+        assert!(should_upgrade(Some("0.1.0"), Some("0.1.1")));
+        assert!(should_upgrade(None, Some("0.1.0")));
+        assert!(!should_upgrade(Some("0.1.0"), None));
+        assert!(!should_upgrade(Some("0.2.0"), Some("0.1.9")));
+    }
+}
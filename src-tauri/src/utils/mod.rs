@@ -1,2 +1,23 @@
+pub mod backup;
+pub mod chat_stream;
+pub mod checksum;
+pub mod config;
+pub mod context;
+pub mod conversations;
+pub mod embedded;
+pub mod error;
+pub mod export;
 pub mod fs;
+pub mod health;
+pub mod installer;
+pub mod net;
 pub mod paths;
+pub mod platform;
+pub mod process;
+pub mod profiles;
+pub mod search;
+pub mod settings;
+pub mod supervisor;
+pub mod toml_doc;
+pub mod validation;
+pub mod version;
@@ -0,0 +1,139 @@
+use std::net::{IpAddr, Ipv4Addr, TcpListener};
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::utils::error::EasyClawError;
+use crate::utils::toml_doc::TomlDocument;
+
+/// The gateway port EasyClaw prefers when it is free.
+pub const DEFAULT_GATEWAY_PORT: u16 = 18789;
+
+/// Ports EasyClaw falls back to when the preferred gateway port is taken.
+pub const GATEWAY_PORT_RANGE: RangeInclusive<u16> = 18789..=18819;
+
+/// Host the gateway binds to when config.toml doesn't say otherwise.
+pub const DEFAULT_GATEWAY_HOST: &str = "127.0.0.1";
+
+/// Where the frontend can reach the gateway.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GatewayEndpoint {
+    pub host: String,
+    pub port: u16,
+    /// Base URL without trailing slash, e.g. `http://127.0.0.1:18789`.
+    pub url: String,
+}
+
+impl GatewayEndpoint {
+    pub fn new(host: &str, port: u16) -> Self {
+        // A wildcard bind address is reachable on loopback
+        let connect_host = match host.parse::<IpAddr>() {
+            Ok(ip) if ip.is_unspecified() => Ipv4Addr::LOCALHOST.to_string(),
+            Ok(IpAddr::V6(ip)) => format!("[{}]", ip),
+            _ => host.to_string(),
+        };
+        Self {
+            host: host.to_string(),
+            port,
+            url: format!("http://{}:{}", connect_host, port),
+        }
+    }
+}
+
+/// Reads the gateway endpoint from the [gateway] section of config.toml,
+/// falling back to EasyClaw's defaults for missing values.
+pub fn gateway_endpoint(config_path: &Path) -> Result<GatewayEndpoint, EasyClawError> {
+    let doc = if config_path.exists() {
+        TomlDocument::load(config_path)?
+    } else {
+        TomlDocument::default()
+    };
+
+    let host = doc
+        .get("gateway.host")
+        .and_then(|item| item.as_str())
+        .unwrap_or(DEFAULT_GATEWAY_HOST);
+    let port = doc
+        .get("gateway.port")
+        .and_then(|item| item.as_integer())
+        .and_then(|port| u16::try_from(port).ok())
+        .unwrap_or(DEFAULT_GATEWAY_PORT);
+
+    Ok(GatewayEndpoint::new(host, port))
+}
+
+/// Returns true if nothing is listening on `host:port`, i.e. we could bind it ourselves.
+pub fn is_port_available(host: &str, port: u16) -> bool {
+    TcpListener::bind((host, port)).is_ok()
+}
+
+/// Returns `preferred` if it is free, otherwise the first free port in `range`.
+pub fn find_available_port(host: &str, preferred: u16, range: RangeInclusive<u16>) -> Option<u16> {
+    std::iter::once(preferred)
+        .chain(range.filter(|port| *port != preferred))
+        .find(|port| is_port_available(host, *port))
+}
+
+/// Picks the gateway port for `host`: `preferred` if free, otherwise the first free
+/// port in `GATEWAY_PORT_RANGE`.
+pub fn select_gateway_port(host: &str, preferred: u16) -> Result<u16, EasyClawError> {
+    find_available_port(host, preferred, GATEWAY_PORT_RANGE).ok_or_else(|| {
+        EasyClawError::PortInUse(format!(
+            "No free gateway port on {} (tried {} and {}-{})",
+            host,
+            preferred,
+            GATEWAY_PORT_RANGE.start(),
+            GATEWAY_PORT_RANGE.end()
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_port_available_detects_bound_port() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert!(!is_port_available("127.0.0.1", port));
+        drop(listener);
+        assert!(is_port_available("127.0.0.1", port));
+    }
+
+    #[test]
+    fn test_find_available_port_skips_taken_preferred_port() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let taken = listener.local_addr().unwrap().port();
+
+        let port = find_available_port("127.0.0.1", taken, taken..=taken);
+        assert_eq!(port, None);
+
+        let free = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let candidate = free.local_addr().unwrap().port();
+        drop(free);
+        let port = find_available_port("127.0.0.1", taken, candidate..=candidate);
+        assert_eq!(port, Some(candidate));
+    }
+
+    #[test]
+    fn test_gateway_endpoint_reads_config_with_defaults() {
+        let tmp = TempDir::new().unwrap();
+        let config_path = tmp.path().join("config.toml");
+
+        assert_eq!(
+            gateway_endpoint(&config_path).unwrap(),
+            GatewayEndpoint::new(DEFAULT_GATEWAY_HOST, DEFAULT_GATEWAY_PORT)
+        );
+
+        fs::write(&config_path, "[gateway]\nport = 18790\nhost = \"0.0.0.0\"\n").unwrap();
+        let endpoint = gateway_endpoint(&config_path).unwrap();
+        assert_eq!(endpoint.host, "0.0.0.0");
+        assert_eq!(endpoint.port, 18790);
+        assert_eq!(endpoint.url, "http://127.0.0.1:18790");
+    }
+}
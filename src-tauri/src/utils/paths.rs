@@ -1,29 +1,107 @@
+use std::env;
+use std::ffi::OsString;
 use std::path::PathBuf;
 
-/// Returns the ZeroClaw root directory: ~/.zeroclaw
-pub fn zeroclaw_dir() -> Result<PathBuf, String> {
-    let home = dirs::home_dir().ok_or_else(|| "Cannot determine home directory".to_string())?;
-    Ok(home.join(".zeroclaw"))
+use log::warn;
+use serde::Serialize;
+
+use crate::utils::error::EasyClawError;
+use crate::utils::profiles::{profile_exists, profile_root, DEFAULT_PROFILE};
+use crate::utils::settings;
+
+/// Environment variable that overrides the ZeroClaw root. Also passed to every ZeroClaw
+/// process EasyClaw starts, so they use the same root.
+pub const ZEROCLAW_HOME_ENV: &str = "ZEROCLAW_HOME";
+
+/// A file with this name next to the EasyClaw executable enables portable mode.
+const PORTABLE_MARKER: &str = "portable";
+
+/// Where the ZeroClaw root came from, in order of precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HomeSource {
+    /// The `ZEROCLAW_HOME` environment variable.
+    Env,
+    /// Portable mode: `<exe dir>/data/zeroclaw`.
+    Portable,
+    /// The `zeroclaw_home` app setting.
+    Settings,
+    /// ~/.zeroclaw
+    Default,
 }
 
-/// Returns the ZeroClaw workspace directory: ~/.zeroclaw/workspace
-pub fn workspace_dir() -> Result<PathBuf, String> {
-    Ok(zeroclaw_dir()?.join("workspace"))
+/// The resolved ZeroClaw root directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ZeroClawHome {
+    /// Root of the active profile: config, workspace, backups.
+    pub path: PathBuf,
+    /// Root shared by all profiles: the default profile, `bin/` and `profiles/`.
+    pub base: PathBuf,
+    pub profile: String,
+    pub source: HomeSource,
 }
 
-/// Returns the ZeroClaw bin directory: ~/.zeroclaw/bin
-pub fn bin_dir() -> Result<PathBuf, String> {
-    Ok(zeroclaw_dir()?.join("bin"))
+/// Resolves the ZeroClaw root: `ZEROCLAW_HOME`, then portable mode, then the
+/// `zeroclaw_home` app setting, then ~/.zeroclaw; then the active profile within it.
+/// `ZEROCLAW_HOME` pins a single root and ignores the active profile.
+pub fn resolve_zeroclaw_home() -> Result<ZeroClawHome, String> {
+    let settings = match settings_file_path().map_err(EasyClawError::from).and_then(|path| settings::load_settings(&path)) {
+        Ok(settings) => settings,
+        Err(e) => {
+            warn!("[resolve_zeroclaw_home] Ignoring app settings: {}", e);
+            settings::AppSettings::default()
+        }
+    };
+    let base = resolve_home(env::var_os(ZEROCLAW_HOME_ENV), portable_data_dir(), settings.zeroclaw_home, dirs::home_dir())?;
+    Ok(apply_profile(base, settings.active_profile.as_deref()))
 }
 
-/// Returns the ZeroClaw binary path: ~/.zeroclaw/bin/zeroclaw
-pub fn zeroclaw_bin_path() -> Result<PathBuf, String> {
-    Ok(bin_dir()?.join("zeroclaw"))
+fn resolve_home(
+    env_home: Option<OsString>,
+    portable_dir: Option<PathBuf>,
+    setting: Option<PathBuf>,
+    home_dir: Option<PathBuf>,
+) -> Result<ZeroClawHome, String> {
+    let (path, source) = if let Some(path) = env_home.filter(|value| !value.is_empty()) {
+        (PathBuf::from(path), HomeSource::Env)
+    } else if let Some(data_dir) = portable_dir {
+        (data_dir.join("zeroclaw"), HomeSource::Portable)
+    } else if let Some(path) = setting.filter(|path| !path.as_os_str().is_empty()) {
+        (path, HomeSource::Settings)
+    } else {
+        let home = home_dir.ok_or_else(|| "Cannot determine home directory".to_string())?;
+        (home.join(".zeroclaw"), HomeSource::Default)
+    };
+    Ok(ZeroClawHome { base: path.clone(), path, profile: DEFAULT_PROFILE.to_string(), source })
 }
 
-/// Returns the ZeroClaw config file path: ~/.zeroclaw/config.toml
-pub fn config_file_path() -> Result<PathBuf, String> {
-    Ok(zeroclaw_dir()?.join("config.toml"))
+fn apply_profile(home: ZeroClawHome, profile: Option<&str>) -> ZeroClawHome {
+    match profile {
+        Some(name) if home.source != HomeSource::Env && profile_exists(&home.base, name) => ZeroClawHome {
+            path: profile_root(&home.base, name),
+            profile: name.to_string(),
+            ..home
+        },
+        _ => home,
+    }
+}
+
+/// In portable mode (a `portable` file next to the executable) returns `<exe dir>/data`,
+/// which holds both the ZeroClaw root and EasyClaw's settings.
+fn portable_data_dir() -> Option<PathBuf> {
+    let exe_dir = env::current_exe().ok()?.parent()?.to_path_buf();
+    exe_dir.join(PORTABLE_MARKER).is_file().then(|| exe_dir.join("data"))
+}
+
+/// Returns EasyClaw's own settings file: <platform config dir>/com.easyclaw.app/settings.json,
+/// or `<exe dir>/data/settings.json` in portable mode.
+/// Kept outside ~/.zeroclaw since it describes the client, not ZeroClaw.
+pub fn settings_file_path() -> Result<PathBuf, String> {
+    if let Some(data_dir) = portable_data_dir() {
+        return Ok(data_dir.join("settings.json"));
+    }
+    let config_dir = dirs::config_dir().ok_or_else(|| "Cannot determine config directory".to_string())?;
+    Ok(config_dir.join("com.easyclaw.app").join("settings.json"))
 }
 
 #[cfg(test)]
@@ -31,38 +109,57 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_zeroclaw_dir_is_under_home() {
-        let dir = zeroclaw_dir().unwrap();
-        let home = dirs::home_dir().unwrap();
-        assert!(dir.starts_with(&home));
-        assert!(dir.ends_with(".zeroclaw"));
+    fn test_default_home_is_under_home() {
+        let home = resolve_home(None, None, None, Some(PathBuf::from("/home/me"))).unwrap();
+        assert_eq!(home, ZeroClawHome {
+            path: PathBuf::from("/home/me/.zeroclaw"),
+            base: PathBuf::from("/home/me/.zeroclaw"),
+            profile: DEFAULT_PROFILE.to_string(),
+            source: HomeSource::Default,
+        });
     }
 
     #[test]
-    fn test_workspace_dir_is_under_zeroclaw_dir() {
-        let ws = workspace_dir().unwrap();
-        let zc = zeroclaw_dir().unwrap();
-        assert!(ws.starts_with(&zc));
-        assert!(ws.ends_with("workspace"));
+    fn test_home_precedence_env_then_portable_then_settings() {
+        let env = Some(OsString::from("/tmp/zc-test"));
+        let portable = Some(PathBuf::from("/opt/easyclaw/data"));
+        let setting = Some(PathBuf::from("/mnt/data/zeroclaw"));
+        let home = Some(PathBuf::from("/home/me"));
+
+        let resolved = resolve_home(env, portable.clone(), setting.clone(), home.clone()).unwrap();
+        assert_eq!((resolved.path, resolved.source), (PathBuf::from("/tmp/zc-test"), HomeSource::Env));
+
+        let resolved = resolve_home(None, portable, setting.clone(), home.clone()).unwrap();
+        assert_eq!((resolved.path, resolved.source), (PathBuf::from("/opt/easyclaw/data/zeroclaw"), HomeSource::Portable));
+
+        let resolved = resolve_home(Some(OsString::new()), None, setting, home).unwrap();
+        assert_eq!((resolved.path, resolved.source), (PathBuf::from("/mnt/data/zeroclaw"), HomeSource::Settings));
     }
 
     #[test]
-    fn test_config_file_path_ends_with_toml() {
-        let cfg = config_file_path().unwrap();
-        assert!(cfg.to_string_lossy().ends_with("config.toml"));
+    fn test_active_profile_root_is_under_base() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("profiles/work")).unwrap();
+        let base = resolve_home(None, None, Some(tmp.path().to_path_buf()), None).unwrap();
+
+        let home = apply_profile(base.clone(), Some("work"));
+        assert_eq!((home.path, home.base, home.profile.as_str()), (tmp.path().join("profiles/work"), tmp.path().to_path_buf(), "work"));
+
+        // Unknown profiles and ZEROCLAW_HOME fall back to the base root
+        assert_eq!(apply_profile(base, Some("missing")).path, tmp.path());
+        let pinned = resolve_home(Some(tmp.path().into()), None, None, None).unwrap();
+        assert_eq!(apply_profile(pinned, Some("work")).path, tmp.path());
     }
 
     #[test]
-    fn test_bin_dir_is_under_zeroclaw_dir() {
-        let bd = bin_dir().unwrap();
-        let zc = zeroclaw_dir().unwrap();
-        assert!(bd.starts_with(&zc));
-        assert!(bd.ends_with("bin"));
+    fn test_home_without_home_dir_fails() {
+        assert!(resolve_home(None, None, None, None).is_err());
     }
 
     #[test]
-    fn test_zeroclaw_bin_path_ends_with_zeroclaw() {
-        let bp = zeroclaw_bin_path().unwrap();
-        assert!(bp.to_string_lossy().contains(".zeroclaw/bin/zeroclaw"));
+    fn test_settings_file_path_is_outside_zeroclaw_dir() {
+        let settings = settings_file_path().unwrap();
+        assert!(!settings.starts_with(resolve_zeroclaw_home().unwrap().path));
+        assert!(settings.ends_with("com.easyclaw.app/settings.json"));
     }
 }
@@ -0,0 +1,249 @@
+use std::env::consts;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::utils::error::EasyClawError;
+
+/// Target triple the app was built for (set by build.rs), e.g. `aarch64-apple-darwin`.
+/// Bundled binaries live under `resources/bin/<TARGET_TRIPLE>/`.
+pub const TARGET_TRIPLE: &str = env!("EASYCLAW_TARGET_TRIPLE");
+
+/// File name of the ZeroClaw executable on this platform.
+pub const BINARY_NAME: &str = if cfg!(windows) { "zeroclaw.exe" } else { "zeroclaw" };
+
+/// Bytes read from the start of a binary to identify its format.
+const HEADER_LEN: usize = 4096;
+
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+const MACHO_64_MAGIC: &[u8] = &[0xcf, 0xfa, 0xed, 0xfe];
+const MACHO_FAT_MAGIC: &[u8] = &[0xca, 0xfe, 0xba, 0xbe];
+const PE_MAGIC: &[u8] = b"MZ";
+
+/// Path of this platform's bundled binary relative to `resources/bin`, as recorded in the
+/// build-time checksum manifest, e.g. `x86_64-unknown-linux-gnu/zeroclaw`.
+pub fn bundled_binary_name() -> String {
+    format!("{}/{}", TARGET_TRIPLE, BINARY_NAME)
+}
+
+/// Picks the bundled binary for the running platform from `bin_dir` (`resources/bin`) and
+/// checks that its header matches this OS and architecture.
+pub fn select_bundled_binary(bin_dir: &Path) -> Result<PathBuf, EasyClawError> {
+    let path = bin_dir.join(TARGET_TRIPLE).join(BINARY_NAME);
+    if !path.is_file() {
+        return Err(EasyClawError::UnsupportedPlatform(format!(
+            "No ZeroClaw build for this platform ({}): {} not found",
+            TARGET_TRIPLE,
+            path.display()
+        )));
+    }
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    File::open(&path)
+        .and_then(|file| file.take(HEADER_LEN as u64).read_to_end(&mut header))
+        .map_err(|e| EasyClawError::io(format!("Failed to read {}", path.display()), &e))?;
+    check_executable(&header, consts::OS, consts::ARCH).map_err(|e| {
+        EasyClawError::UnsupportedPlatform(format!(
+            "No ZeroClaw build for this platform ({}): {} {}",
+            TARGET_TRIPLE,
+            path.display(),
+            e
+        ))
+    })?;
+
+    Ok(path)
+}
+
+/// Checks that `header` starts an executable for `os`/`arch` (as in `std::env::consts`).
+fn check_executable(header: &[u8], os: &str, arch: &str) -> Result<(), String> {
+    let (format, archs) = detect_executable(header).ok_or("is not a recognized executable")?;
+    let expected = match os {
+        "linux" => "ELF",
+        "macos" => "Mach-O",
+        "windows" => "PE",
+        other => return Err(format!("cannot be checked on unsupported OS '{}'", other)),
+    };
+    if format != expected || !archs.contains(&arch) {
+        return Err(format!(
+            "is a {} {} executable, expected {} {}",
+            format,
+            if archs.is_empty() { "unknown-arch".to_string() } else { archs.join("+") },
+            expected,
+            arch
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the executable format and the architectures it contains (several for a
+/// universal Mach-O), or None if `header` isn't an ELF, Mach-O or PE file.
+fn detect_executable(header: &[u8]) -> Option<(&'static str, Vec<&'static str>)> {
+    if header.starts_with(ELF_MAGIC) {
+        // e_ident[EI_DATA]: 1 = little endian, 2 = big endian
+        let machine = match header.get(5)? {
+            1 => u16::from_le_bytes(read_array(header, 18)?),
+            _ => u16::from_be_bytes(read_array(header, 18)?),
+        };
+        return Some(("ELF", elf_arch(machine).into_iter().collect()));
+    }
+    if header.starts_with(MACHO_64_MAGIC) {
+        let cpu_type = u32::from_le_bytes(read_array(header, 4)?);
+        return Some(("Mach-O", macho_arch(cpu_type).into_iter().collect()));
+    }
+    if header.starts_with(MACHO_FAT_MAGIC) {
+        // The count is unverified (0xCAFEBABE also starts Java class files), so only
+        // entries that fit in the header are read
+        let count = (u32::from_be_bytes(read_array(header, 4)?) as usize).min(header.len().saturating_sub(8) / 20);
+        let archs = (0..count)
+            .filter_map(|i| read_array(header, 8 + 20 * i))
+            .filter_map(|bytes| macho_arch(u32::from_be_bytes(bytes)))
+            .collect();
+        return Some(("Mach-O", archs));
+    }
+    if header.starts_with(PE_MAGIC) {
+        let pe_offset = u32::from_le_bytes(read_array(header, 0x3c)?) as usize;
+        if header.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+            return None;
+        }
+        let machine = u16::from_le_bytes(read_array(header, pe_offset + 4)?);
+        return Some(("PE", pe_arch(machine).into_iter().collect()));
+    }
+    None
+}
+
+fn read_array<const N: usize>(bytes: &[u8], offset: usize) -> Option<[u8; N]> {
+    bytes.get(offset..offset + N)?.try_into().ok()
+}
+
+fn elf_arch(machine: u16) -> Option<&'static str> {
+    match machine {
+        62 => Some("x86_64"),
+        183 => Some("aarch64"),
+        _ => None,
+    }
+}
+
+fn macho_arch(cpu_type: u32) -> Option<&'static str> {
+    match cpu_type {
+        0x0100_0007 => Some("x86_64"),
+        0x0100_000c => Some("aarch64"),
+        _ => None,
+    }
+}
+
+fn pe_arch(machine: u16) -> Option<&'static str> {
+    match machine {
+        0x8664 => Some("x86_64"),
+        0xaa64 => Some("aarch64"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn elf(machine: u16) -> Vec<u8> {
+        let mut header = vec![0u8; 64];
+        header[..4].copy_from_slice(ELF_MAGIC);
+        header[5] = 1;
+        header[18..20].copy_from_slice(&machine.to_le_bytes());
+        header
+    }
+
+    fn macho(cpu_type: u32) -> Vec<u8> {
+        let mut header = MACHO_64_MAGIC.to_vec();
+        header.extend_from_slice(&cpu_type.to_le_bytes());
+        header
+    }
+
+    fn pe(machine: u16) -> Vec<u8> {
+        let mut header = vec![0u8; 0x86];
+        header[..2].copy_from_slice(PE_MAGIC);
+        header[0x3c..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        header[0x80..0x84].copy_from_slice(b"PE\0\0");
+        header[0x84..0x86].copy_from_slice(&machine.to_le_bytes());
+        header
+    }
+
+    #[test]
+    fn test_check_executable_accepts_matching_platform() {
+        assert!(check_executable(&elf(62), "linux", "x86_64").is_ok());
+        assert!(check_executable(&elf(183), "linux", "aarch64").is_ok());
+        assert!(check_executable(&macho(0x0100_000c), "macos", "aarch64").is_ok());
+        assert!(check_executable(&pe(0x8664), "windows", "x86_64").is_ok());
+    }
+
+    #[test]
+    fn test_check_executable_accepts_universal_macho() {
+        let mut fat = MACHO_FAT_MAGIC.to_vec();
+        fat.extend_from_slice(&2u32.to_be_bytes());
+        for cpu_type in [0x0100_0007u32, 0x0100_000c] {
+            fat.extend_from_slice(&cpu_type.to_be_bytes());
+            fat.extend_from_slice(&[0u8; 16]);
+        }
+        assert!(check_executable(&fat, "macos", "x86_64").is_ok());
+        assert!(check_executable(&fat, "macos", "aarch64").is_ok());
+    }
+
+    #[test]
+    fn test_detect_executable_bounds_fat_header_count() {
+        // A Java class file: the magic matches, the "count" is the class version
+        let mut class = MACHO_FAT_MAGIC.to_vec();
+        class.extend_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+        class.extend_from_slice(&[0u8; 64]);
+        assert_eq!(detect_executable(&class), Some(("Mach-O", vec![])));
+    }
+
+    #[test]
+    fn test_check_executable_rejects_other_platforms() {
+        let err = check_executable(&macho(0x0100_000c), "linux", "x86_64").unwrap_err();
+        assert_eq!(err, "is a Mach-O aarch64 executable, expected ELF x86_64");
+        assert!(check_executable(&elf(183), "linux", "x86_64").is_err());
+        assert!(check_executable(&pe(0xaa64), "windows", "x86_64").is_err());
+        assert!(check_executable(b"#!/bin/sh\n", "linux", "x86_64").is_err());
+        assert!(check_executable(b"MZ", "windows", "x86_64").is_err());
+    }
+
+    #[test]
+    fn test_select_bundled_binary_missing_platform() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("some-other-triple")).unwrap();
+        fs::write(tmp.path().join("some-other-triple").join(BINARY_NAME), elf(62)).unwrap();
+
+        let err = select_bundled_binary(tmp.path()).unwrap_err();
+        assert_eq!(err.code(), "unsupported_platform");
+        assert!(err.detail().contains(TARGET_TRIPLE), "{}", err);
+    }
+
+    #[test]
+    fn test_select_bundled_binary_rejects_wrong_format() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join(TARGET_TRIPLE);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(BINARY_NAME), "#!/bin/sh\n").unwrap();
+
+        let err = select_bundled_binary(tmp.path()).unwrap_err();
+        assert_eq!(err.code(), "unsupported_platform");
+        assert!(err.detail().contains("not a recognized executable"), "{}", err);
+    }
+
+    #[test]
+    fn test_select_bundled_binary_accepts_host_binary() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join(TARGET_TRIPLE);
+        fs::create_dir_all(&dir).unwrap();
+        // The running test executable is a native binary for this platform
+        let mut header = Vec::new();
+        File::open(std::env::current_exe().unwrap())
+            .unwrap()
+            .take(HEADER_LEN as u64)
+            .read_to_end(&mut header)
+            .unwrap();
+        fs::write(dir.join(BINARY_NAME), header).unwrap();
+
+        assert_eq!(select_bundled_binary(tmp.path()).unwrap(), dir.join(BINARY_NAME));
+    }
+}
@@ -0,0 +1,494 @@
+use std::ffi::OsString;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::process::Stdio;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+use tokio::sync::watch;
+
+/// Maximum bytes kept per output stream; the rest is drained and dropped.
+pub const OUTPUT_LIMIT: usize = 256 * 1024;
+
+/// Longer lines are cut to this many bytes before they are streamed.
+const MAX_LINE_LENGTH: usize = 8 * 1024;
+
+/// How long output is still read after the child exited. A process it started in the
+/// background (e.g. a daemon) may keep the pipes open long after that.
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Event carrying one line of process output to the frontend while the process runs.
+pub const PROCESS_OUTPUT_EVENT: &str = "process://output";
+
+/// Which stream an output line came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Payload of `process://output`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProcessOutputEvent {
+    /// What the process is doing, e.g. `onboard` or `service_install`.
+    pub step: String,
+    pub stream: OutputStream,
+    pub line: String,
+}
+
+/// Receives each line of output while a process runs.
+pub type LineSink<'a> = &'a (dyn Fn(OutputStream, &str) + Send + Sync);
+
+/// Limits and environment for a single process run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessOptions {
+    /// The process is killed once this elapses.
+    pub timeout: Duration,
+    /// Maximum bytes captured per stream.
+    pub output_limit: usize,
+    /// Variables added to the inherited environment.
+    pub env: Vec<(OsString, OsString)>,
+}
+
+impl ProcessOptions {
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self { timeout, output_limit: OUTPUT_LIMIT, env: Vec::new() }
+    }
+
+    pub fn with_env(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+}
+
+/// Captured result of a finished process.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessOutput {
+    /// Exit code, or None if the process was killed by a signal.
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// True if either stream exceeded the output limit.
+    pub truncated: bool,
+}
+
+impl ProcessOutput {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// Why a process run failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessError {
+    /// The program could not be started at all. `kind` is that of the underlying I/O error.
+    Spawn { program: String, message: String, kind: io::ErrorKind },
+    /// The program didn't finish within the timeout and was killed.
+    Timeout { program: String, timeout: Duration },
+    /// The program ran but exited unsuccessfully. Its output is kept for diagnostics.
+    NonZeroExit { program: String, output: ProcessOutput },
+}
+
+impl ProcessError {
+    fn spawn(program: &str, e: &io::Error) -> Self {
+        ProcessError::Spawn { program: program.to_string(), message: e.to_string(), kind: e.kind() }
+    }
+
+    /// The captured output, if the process ran to completion.
+    pub fn output(&self) -> Option<&ProcessOutput> {
+        match self {
+            ProcessError::NonZeroExit { output, .. } => Some(output),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ProcessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcessError::Spawn { program, message, .. } => write!(f, "Failed to run {}: {}", program, message),
+            ProcessError::Timeout { program, timeout } => {
+                write!(f, "{} timed out after {}s", program, timeout.as_secs())
+            }
+            ProcessError::NonZeroExit { program, output } => write!(
+                f,
+                "{} failed (exit {}): {}",
+                program,
+                output.code.unwrap_or(-1),
+                output.stderr.trim()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProcessError {}
+
+impl From<ProcessError> for String {
+    fn from(e: ProcessError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Runs `program args...` without blocking the async runtime. Output is captured up to
+/// `options.output_limit` per stream, and the process is killed if it outlives
+/// `options.timeout`. A non-zero exit is an error that still carries the output.
+pub async fn run_process(
+    program: &Path,
+    args: &[&str],
+    options: &ProcessOptions,
+) -> Result<ProcessOutput, ProcessError> {
+    run_process_streaming(program, args, options, &discard_lines).await
+}
+
+/// Like `run_process`, but also hands every output line to `on_line` as soon as it is read.
+pub async fn run_process_streaming(
+    program: &Path,
+    args: &[&str],
+    options: &ProcessOptions,
+    on_line: LineSink<'_>,
+) -> Result<ProcessOutput, ProcessError> {
+    let label = program_label(program, args);
+
+    let mut child = Command::new(program)
+        .args(args)
+        .envs(options.env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| ProcessError::spawn(&label, &e))?;
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let limit = options.output_limit;
+    let (exited_tx, exited) = watch::channel(false);
+    let finished = tokio::time::timeout(options.timeout, async {
+        let wait = async {
+            let status = child.wait().await;
+            let _ = exited_tx.send(true);
+            status
+        };
+        let (stdout, stderr, status) = tokio::join!(
+            read_lines(stdout, OutputStream::Stdout, limit, on_line, exited.clone()),
+            read_lines(stderr, OutputStream::Stderr, limit, on_line, exited.clone()),
+            wait
+        );
+        (stdout, stderr, status)
+    })
+    .await;
+
+    let ((stdout, stdout_truncated), (stderr, stderr_truncated), status) = match finished {
+        Ok(result) => result,
+        Err(_) => {
+            let _ = child.kill().await;
+            return Err(ProcessError::Timeout { program: label, timeout: options.timeout });
+        }
+    };
+    let status = status.map_err(|e| ProcessError::spawn(&label, &e))?;
+
+    let output = ProcessOutput {
+        code: status.code(),
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        truncated: stdout_truncated || stderr_truncated,
+    };
+    if output.success() {
+        Ok(output)
+    } else {
+        Err(ProcessError::NonZeroExit { program: label, output })
+    }
+}
+
+/// Future returned by `ProcessRunner::run`.
+pub type ProcessFuture<'a> = Pin<Box<dyn Future<Output = Result<ProcessOutput, ProcessError>> + Send + 'a>>;
+
+/// Runs external programs on behalf of the commands, so tests can substitute scripted
+/// results for real zeroclaw processes.
+pub trait ProcessRunner: Send + Sync {
+    /// Same contract as `run_process_streaming`.
+    fn run<'a>(
+        &'a self,
+        program: &'a Path,
+        args: &'a [&'a str],
+        options: &'a ProcessOptions,
+        on_line: LineSink<'a>,
+    ) -> ProcessFuture<'a>;
+}
+
+/// Runs programs as real child processes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRunner;
+
+impl ProcessRunner for SystemRunner {
+    fn run<'a>(
+        &'a self,
+        program: &'a Path,
+        args: &'a [&'a str],
+        options: &'a ProcessOptions,
+        on_line: LineSink<'a>,
+    ) -> ProcessFuture<'a> {
+        Box::pin(run_process_streaming(program, args, options, on_line))
+    }
+}
+
+/// A sink that emits each line as a `process://output` event for `step`.
+pub fn emit_lines<'a>(app: &'a AppHandle, step: &'a str) -> impl Fn(OutputStream, &str) + Send + Sync + 'a {
+    move |stream, line| {
+        let event = ProcessOutputEvent { step: step.to_string(), stream, line: line.to_string() };
+        if let Err(e) = app.emit(PROCESS_OUTPUT_EVENT, &event) {
+            warn!("[emit_lines] Failed to emit {}: {}", PROCESS_OUTPUT_EVENT, e);
+        }
+    }
+}
+
+/// A sink that ignores output lines.
+pub fn discard_lines(_stream: OutputStream, _line: &str) {}
+
+/// Logs a process's exit code and output under the caller's tag.
+pub fn log_output(caller: &str, output: &ProcessOutput) {
+    info!("[{}] exit code: {:?}", caller, output.code);
+    info!("[{}] stdout: {}", caller, output.stdout);
+    if !output.stderr.is_empty() {
+        warn!("[{}] stderr: {}", caller, output.stderr);
+    }
+    if output.truncated {
+        warn!("[{}] output truncated to {} bytes per stream", caller, OUTPUT_LIMIT);
+    }
+}
+
+/// Human-readable command line, e.g. `zeroclaw service start`.
+fn program_label(program: &Path, args: &[&str]) -> String {
+    let name = program
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| program.display().to_string());
+    std::iter::once(name.as_str()).chain(args.iter().copied()).collect::<Vec<_>>().join(" ")
+}
+
+/// Reads `reader` to the end, or until DRAIN_TIMEOUT after `exited` turns true, passing
+/// each line to `on_line` and keeping at most `limit` bytes. Returns the bytes and
+/// whether anything was dropped.
+async fn read_lines<R: AsyncRead + Unpin>(
+    reader: Option<R>,
+    stream: OutputStream,
+    limit: usize,
+    on_line: LineSink<'_>,
+    mut exited: watch::Receiver<bool>,
+) -> (Vec<u8>, bool) {
+    let mut buf = Vec::new();
+    let mut truncated = false;
+    let Some(mut reader) = reader else {
+        return (buf, truncated);
+    };
+
+    let mut line = Vec::new();
+    let emit = |line: &mut Vec<u8>| {
+        let text = String::from_utf8_lossy(line);
+        on_line(stream, text.trim_end_matches('\r'));
+        line.clear();
+    };
+
+    let drained = async {
+        let _ = exited.wait_for(|exited| *exited).await;
+        tokio::time::sleep(DRAIN_TIMEOUT).await;
+    };
+    tokio::pin!(drained);
+
+    let mut chunk = [0u8; 8192];
+    loop {
+        let n = tokio::select! {
+            read = reader.read(&mut chunk) => match read {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            },
+            // The child is gone; whoever still holds the pipe isn't ours to wait for
+            _ = &mut drained => break,
+        };
+        let room = limit.saturating_sub(buf.len());
+        truncated |= n > room;
+        buf.extend_from_slice(&chunk[..n.min(room)]);
+
+        for &byte in &chunk[..n] {
+            if byte == b'\n' {
+                emit(&mut line);
+            } else if line.len() < MAX_LINE_LENGTH {
+                line.push(byte);
+            }
+        }
+    }
+    if !line.is_empty() {
+        emit(&mut line);
+    }
+    (buf, truncated)
+}
+
+/// Scripted answer of a `ScriptedRunner` to the given arguments.
+#[cfg(test)]
+type Respond = dyn Fn(&[&str]) -> Result<ProcessOutput, ProcessError> + Send + Sync;
+
+/// A `ProcessRunner` for tests: records each call as `args...` and answers with `respond`.
+#[cfg(test)]
+pub(crate) struct ScriptedRunner {
+    calls: std::sync::Mutex<Vec<String>>,
+    respond: Box<Respond>,
+}
+
+#[cfg(test)]
+impl ScriptedRunner {
+    pub fn new(respond: impl Fn(&[&str]) -> Result<ProcessOutput, ProcessError> + Send + Sync + 'static) -> Self {
+        Self { calls: std::sync::Mutex::new(Vec::new()), respond: Box::new(respond) }
+    }
+
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl ProcessRunner for ScriptedRunner {
+    fn run<'a>(
+        &'a self,
+        _program: &'a Path,
+        args: &'a [&'a str],
+        _options: &'a ProcessOptions,
+        on_line: LineSink<'a>,
+    ) -> ProcessFuture<'a> {
+        self.calls.lock().unwrap().push(args.join(" "));
+        let result = (self.respond)(args);
+        if let Ok(output) = &result {
+            output.stdout.lines().for_each(|line| on_line(OutputStream::Stdout, line));
+        }
+        Box::pin(async move { result })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn create_script(dir: &Path, body: &str) -> PathBuf {
+        let bin = dir.join("zeroclaw");
+        fs::write(&bin, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&bin, fs::Permissions::from_mode(0o755)).unwrap();
+        bin
+    }
+
+    fn options() -> ProcessOptions {
+        ProcessOptions::with_timeout(Duration::from_secs(5))
+    }
+
+    #[tokio::test]
+    async fn test_run_process_captures_output() {
+        let tmp = TempDir::new().unwrap();
+        let bin = create_script(tmp.path(), "echo \"out $1\"\necho err >&2");
+
+        let output = run_process(&bin, &["onboard"], &options()).await.unwrap();
+
+        assert_eq!(
+            output,
+            ProcessOutput { code: Some(0), stdout: "out onboard\n".into(), stderr: "err\n".into(), truncated: false }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_process_passes_extra_env() {
+        let tmp = TempDir::new().unwrap();
+        let bin = create_script(tmp.path(), "echo \"home=$ZEROCLAW_HOME\"");
+
+        let options = options().with_env("ZEROCLAW_HOME", "/tmp/zc-home");
+        let output = run_process(&bin, &[], &options).await.unwrap();
+
+        assert_eq!(output.stdout, "home=/tmp/zc-home\n");
+    }
+
+    #[tokio::test]
+    async fn test_run_process_streaming_reports_lines_as_they_arrive() {
+        let tmp = TempDir::new().unwrap();
+        let bin = create_script(tmp.path(), "echo one\necho oops >&2\nprintf 'two\\r\\nlast'");
+        let lines = std::sync::Mutex::new(Vec::new());
+        let on_line = |stream, line: &str| lines.lock().unwrap().push((stream, line.to_string()));
+
+        let output = run_process_streaming(&bin, &[], &options(), &on_line).await.unwrap();
+
+        let stdout: Vec<_> = lines.lock().unwrap().iter()
+            .filter(|(stream, _)| *stream == OutputStream::Stdout)
+            .map(|(_, line)| line.clone())
+            .collect();
+        assert_eq!(stdout, vec!["one", "two", "last"]);
+        assert!(lines.lock().unwrap().contains(&(OutputStream::Stderr, "oops".to_string())));
+        assert_eq!(output.stdout, "one\ntwo\r\nlast");
+    }
+
+    #[tokio::test]
+    async fn test_run_process_reports_non_zero_exit_with_output() {
+        let tmp = TempDir::new().unwrap();
+        let bin = create_script(tmp.path(), "echo boom >&2\nexit 3");
+
+        let err = run_process(&bin, &["service", "start"], &options()).await.unwrap_err();
+
+        assert_eq!(err.output().unwrap().code, Some(3));
+        assert_eq!(err.to_string(), "zeroclaw service start failed (exit 3): boom");
+    }
+
+    #[tokio::test]
+    async fn test_run_process_reports_spawn_failure() {
+        let tmp = TempDir::new().unwrap();
+
+        let err = run_process(&tmp.path().join("missing"), &[], &options()).await.unwrap_err();
+
+        assert!(matches!(err, ProcessError::Spawn { kind: io::ErrorKind::NotFound, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_run_process_kills_on_timeout() {
+        let tmp = TempDir::new().unwrap();
+        let marker = tmp.path().join("finished");
+        let bin = create_script(tmp.path(), &format!("sleep 2\ntouch \"{}\"", marker.display()));
+
+        let err = run_process(&bin, &[], &ProcessOptions::with_timeout(Duration::from_millis(200)))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ProcessError::Timeout { .. }));
+        tokio::time::sleep(Duration::from_millis(2500)).await;
+        assert!(!marker.exists());
+    }
+
+    #[tokio::test]
+    async fn test_run_process_returns_when_a_background_child_keeps_the_pipes() {
+        let tmp = TempDir::new().unwrap();
+        let bin = create_script(tmp.path(), "sleep 30 &\necho started");
+
+        let started = std::time::Instant::now();
+        let output = run_process(&bin, &[], &ProcessOptions::with_timeout(Duration::from_secs(10))).await.unwrap();
+
+        assert_eq!(output.stdout, "started\n");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_run_process_truncates_large_output() {
+        let tmp = TempDir::new().unwrap();
+        let bin = create_script(tmp.path(), "head -c 10000 /dev/zero");
+
+        let output = run_process(&bin, &[], &ProcessOptions { output_limit: 100, ..options() })
+            .await
+            .unwrap();
+
+        assert_eq!(output.stdout.len(), 100);
+        assert!(output.truncated);
+    }
+}
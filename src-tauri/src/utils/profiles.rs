@@ -0,0 +1,175 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::utils::error::EasyClawError;
+use crate::utils::net::gateway_endpoint;
+
+/// The profile whose root is the base ZeroClaw root itself (~/.zeroclaw by default).
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Directory in the base root that holds the other profiles' roots.
+const PROFILES_DIR: &str = "profiles";
+
+/// Longest accepted profile name.
+const MAX_NAME_LENGTH: usize = 32;
+
+/// A named ZeroClaw root with its own config, workspace and gateway port.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Profile {
+    pub name: String,
+    pub path: PathBuf,
+    pub active: bool,
+    /// True once `zeroclaw onboard` has written the profile's config.toml.
+    pub initialized: bool,
+}
+
+/// Root of profile `name` under `base`: `base` itself for the default profile,
+/// `base/profiles/<name>` otherwise.
+pub fn profile_root(base: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        base.to_path_buf()
+    } else {
+        base.join(PROFILES_DIR).join(name)
+    }
+}
+
+/// Profile names become directory names: 1-32 letters, digits, `-` or `_`.
+pub fn validate_profile_name(name: &str) -> Result<(), EasyClawError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LENGTH
+        && !name.starts_with('-')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(EasyClawError::InvalidInput(format!(
+            "Invalid profile name '{}': use 1-{} letters, digits, '-' or '_'",
+            name, MAX_NAME_LENGTH
+        )))
+    }
+}
+
+pub fn profile_exists(base: &Path, name: &str) -> bool {
+    name == DEFAULT_PROFILE || (validate_profile_name(name).is_ok() && profile_root(base, name).is_dir())
+}
+
+/// Lists the default profile followed by the others in name order.
+pub fn list_profiles(base: &Path, active: &str) -> Result<Vec<Profile>, EasyClawError> {
+    let profiles_dir = base.join(PROFILES_DIR);
+    let mut names: Vec<String> = match fs::read_dir(&profiles_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| name != DEFAULT_PROFILE && validate_profile_name(name).is_ok())
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(EasyClawError::io(format!("Failed to read {}", profiles_dir.display()), &e)),
+    };
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            let path = profile_root(base, &name);
+            Profile {
+                active: name == active,
+                initialized: path.join("config.toml").exists(),
+                name,
+                path,
+            }
+        })
+        .collect())
+}
+
+/// Creates the root directory for a new profile and returns it.
+pub fn create_profile_dir(base: &Path, name: &str) -> Result<PathBuf, EasyClawError> {
+    validate_profile_name(name)?;
+    if profile_exists(base, name) {
+        return Err(EasyClawError::InvalidInput(format!("Profile '{}' already exists", name)));
+    }
+    let root = profile_root(base, name);
+    fs::create_dir_all(&root).map_err(|e| EasyClawError::io(format!("Failed to create {}", root.display()), &e))?;
+    Ok(root)
+}
+
+/// Deletes a profile's root with its config and workspace. The default and the active
+/// profile can't be deleted.
+pub fn delete_profile_dir(base: &Path, name: &str, active: &str) -> Result<(), EasyClawError> {
+    if name == DEFAULT_PROFILE {
+        return Err(EasyClawError::InvalidInput("The default profile can't be deleted".to_string()));
+    }
+    if name == active {
+        return Err(EasyClawError::InvalidInput(format!(
+            "Profile '{}' is active; switch to another profile first",
+            name
+        )));
+    }
+    if !profile_exists(base, name) {
+        return Err(EasyClawError::NotFound(format!("Profile '{}' does not exist", name)));
+    }
+    let root = profile_root(base, name);
+    fs::remove_dir_all(&root).map_err(|e| EasyClawError::io(format!("Failed to delete {}", root.display()), &e))
+}
+
+/// Gateway ports configured by the existing profiles, so a new profile can pick another.
+pub fn configured_ports(base: &Path) -> Vec<u16> {
+    list_profiles(base, DEFAULT_PROFILE)
+        .unwrap_or_default()
+        .iter()
+        .filter(|profile| profile.initialized)
+        .filter_map(|profile| gateway_endpoint(&profile.path.join("config.toml")).ok())
+        .map(|endpoint| endpoint.port)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("work").is_ok());
+        assert!(validate_profile_name("home_2-b").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../etc").is_err());
+        assert!(validate_profile_name("-rf").is_err());
+        assert!(validate_profile_name(&"x".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn test_create_list_and_delete_profiles() {
+        let tmp = TempDir::new().unwrap();
+        let base = tmp.path();
+
+        let work = create_profile_dir(base, "work").unwrap();
+        create_profile_dir(base, "personal").unwrap();
+        fs::write(work.join("config.toml"), "[gateway]\nport = 18790\n").unwrap();
+        assert!(create_profile_dir(base, "work").unwrap_err().detail().contains("already exists"));
+        assert!(create_profile_dir(base, DEFAULT_PROFILE).is_err());
+
+        let profiles = list_profiles(base, "work").unwrap();
+        let summary: Vec<(&str, bool, bool)> =
+            profiles.iter().map(|p| (p.name.as_str(), p.active, p.initialized)).collect();
+        assert_eq!(summary, vec![("default", false, false), ("personal", false, false), ("work", true, true)]);
+        assert_eq!(profiles[2].path, base.join("profiles/work"));
+        assert_eq!(configured_ports(base), vec![18790]);
+
+        assert!(delete_profile_dir(base, "work", "work").unwrap_err().detail().contains("active"));
+        assert!(delete_profile_dir(base, DEFAULT_PROFILE, "work").is_err());
+        delete_profile_dir(base, "personal", "work").unwrap();
+        assert!(!profile_exists(base, "personal"));
+        assert_eq!(delete_profile_dir(base, "personal", "work").unwrap_err().code(), "not_found");
+    }
+
+    #[test]
+    fn test_profile_root() {
+        let base = Path::new("/home/me/.zeroclaw");
+        assert_eq!(profile_root(base, DEFAULT_PROFILE), base);
+        assert_eq!(profile_root(base, "work"), base.join("profiles").join("work"));
+    }
+}
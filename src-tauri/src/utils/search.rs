@@ -0,0 +1,480 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::utils::conversations::{self, MessageStatus};
+use crate::utils::error::EasyClawError;
+
+/// BM25 term-frequency saturation.
+const BM25_K1: f64 = 1.2;
+/// BM25 length normalization.
+const BM25_B: f64 = 0.75;
+
+/// Characters of context kept before the first match in a snippet.
+const SNIPPET_LEAD: usize = 30;
+/// Length of a snippet in characters.
+const SNIPPET_LEN: usize = 120;
+
+/// Extensions of the `workspace/memory` files that are indexed.
+const MEMORY_EXTENSIONS: [&str; 2] = ["md", "txt"];
+
+/// Where a search hit comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HitSource {
+    /// A message of a saved conversation.
+    Message { conversation_id: String, message_id: String },
+    /// A file in `workspace/memory`, relative to it with `/` separators.
+    Memory { path: String },
+}
+
+/// A piece of a snippet; `highlight` marks text matching the query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnippetPart {
+    pub text: String,
+    pub highlight: bool,
+}
+
+/// A ranked search result.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub source: HitSource,
+    /// Conversation title or memory file name.
+    pub title: String,
+    /// Message time in milliseconds since the Unix epoch; None for memory files.
+    pub timestamp: Option<i64>,
+    /// BM25 score; higher is better.
+    pub score: f64,
+    pub snippet: Vec<SnippetPart>,
+}
+
+/// One searchable text with its term counts.
+#[derive(Debug)]
+struct IndexedDoc {
+    source: HitSource,
+    title: String,
+    text: String,
+    timestamp: Option<i64>,
+    terms: HashMap<String, u32>,
+    len: u32,
+}
+
+impl IndexedDoc {
+    fn new(source: HitSource, title: String, text: String, timestamp: Option<i64>) -> Self {
+        let tokens = tokenize(&text, true);
+        let mut terms = HashMap::new();
+        for token in &tokens {
+            *terms.entry(token.clone()).or_insert(0) += 1;
+        }
+        Self { source, title, text, timestamp, len: tokens.len() as u32, terms }
+    }
+}
+
+/// The documents of one file, kept until the file changes.
+#[derive(Debug)]
+struct CachedFile {
+    modified: Option<SystemTime>,
+    size: u64,
+    docs: Vec<IndexedDoc>,
+}
+
+/// Full-text index over saved conversations and, optionally, `workspace/memory`. Files
+/// are re-read only when their size or modification time changes, so the index follows
+/// the conversation store without being told about writes. Held in Tauri managed state.
+#[derive(Debug, Default)]
+pub struct HistoryIndex {
+    files: Mutex<HashMap<PathBuf, CachedFile>>,
+}
+
+impl HistoryIndex {
+    /// Returns up to `limit` hits for `query`, best first.
+    pub fn search(
+        &self,
+        sessions_dir: &Path,
+        memory_dir: Option<&Path>,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>, EasyClawError> {
+        if query.trim().is_empty() {
+            return Err(EasyClawError::InvalidInput("Search query is empty".to_string()));
+        }
+        let mut seen = HashSet::new();
+        let query_terms: Vec<String> = tokenize(query, false).into_iter().filter(|term| seen.insert(term.clone())).collect();
+
+        let mut files = self.files.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut current = Vec::new();
+        for (id, path) in conversations::conversation_files(sessions_dir)? {
+            refresh(&mut files, &path, |path| conversation_docs(path, &id));
+            current.push(path);
+        }
+        if let Some(memory_dir) = memory_dir {
+            for (relative, path) in memory_files(memory_dir) {
+                refresh(&mut files, &path, |path| memory_docs(path, &relative));
+                current.push(path);
+            }
+        }
+        // Forget deleted files, but keep memory files while they're just not searched
+        let searched: HashSet<&PathBuf> = current.iter().collect();
+        files.retain(|path, _| searched.contains(path) || (memory_dir.is_none() && !path.starts_with(sessions_dir)));
+
+        let docs: Vec<&IndexedDoc> = current
+            .iter()
+            .filter_map(|path| files.get(path))
+            .flat_map(|file| file.docs.iter())
+            .collect();
+        Ok(rank(&docs, &query_terms, limit))
+    }
+}
+
+/// Re-reads `path` into the cache if it changed since it was last indexed.
+fn refresh(files: &mut HashMap<PathBuf, CachedFile>, path: &Path, read: impl FnOnce(&Path) -> Option<Vec<IndexedDoc>>) {
+    let Ok(metadata) = fs::metadata(path) else {
+        files.remove(path);
+        return;
+    };
+    let (modified, size) = (metadata.modified().ok(), metadata.len());
+    if files.get(path).is_some_and(|file| file.modified == modified && file.size == size) {
+        return;
+    }
+    match read(path) {
+        Some(docs) => {
+            info!("[search_history] Indexed {} entries from {}", docs.len(), path.display());
+            files.insert(path.to_path_buf(), CachedFile { modified, size, docs });
+        }
+        None => {
+            files.remove(path);
+        }
+    }
+}
+
+fn conversation_docs(path: &Path, id: &str) -> Option<Vec<IndexedDoc>> {
+    let conversation = conversations::read_conversation(path, id)
+        .map_err(|e| warn!("[search_history] Skipping {}: {}", path.display(), e))
+        .ok()?;
+    Some(
+        conversation
+            .messages
+            .into_iter()
+            .filter(|message| message.status == MessageStatus::Done)
+            .map(|message| {
+                let source = HitSource::Message { conversation_id: id.to_string(), message_id: message.id };
+                IndexedDoc::new(source, conversation.title.clone(), message.content, Some(message.timestamp))
+            })
+            .collect(),
+    )
+}
+
+fn memory_docs(path: &Path, relative: &str) -> Option<Vec<IndexedDoc>> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| warn!("[search_history] Skipping {}: {}", path.display(), e))
+        .ok()?;
+    let title = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    Some(vec![IndexedDoc::new(HitSource::Memory { path: relative.to_string() }, title, text, None)])
+}
+
+/// The indexable files under `memory_dir`, recursively, with their relative paths.
+/// Symlinks are skipped, so a link back up the tree can't index files over and over.
+fn memory_files(memory_dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut files = Vec::new();
+    let mut pending = vec![memory_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else { continue };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            // Unlike Path::is_dir, DirEntry::file_type doesn't follow symlinks
+            let Ok(file_type) = entry.file_type() else { continue };
+            let path = entry.path();
+            if file_type.is_symlink() {
+                continue;
+            } else if file_type.is_dir() {
+                pending.push(path);
+            } else if path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| MEMORY_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+            {
+                let relative = path.strip_prefix(memory_dir).unwrap_or(&path);
+                let relative = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+                files.push((relative, path));
+            }
+        }
+    }
+    files
+}
+
+/// Scores `docs` against `query_terms` with BM25 and builds the best `limit` hits.
+fn rank(docs: &[&IndexedDoc], query_terms: &[String], limit: usize) -> Vec<SearchHit> {
+    if docs.is_empty() || query_terms.is_empty() {
+        return Vec::new();
+    }
+
+    let count = docs.len() as f64;
+    let avg_len = (docs.iter().map(|doc| f64::from(doc.len)).sum::<f64>() / count).max(1.0);
+    let idf: Vec<f64> = query_terms
+        .iter()
+        .map(|term| {
+            let df = docs.iter().filter(|doc| doc.terms.contains_key(term)).count() as f64;
+            (1.0 + (count - df + 0.5) / (df + 0.5)).ln()
+        })
+        .collect();
+
+    let mut scored: Vec<(f64, &IndexedDoc)> = docs
+        .iter()
+        .filter_map(|&doc| {
+            let norm = BM25_K1 * (1.0 - BM25_B + BM25_B * f64::from(doc.len) / avg_len);
+            let score: f64 = query_terms
+                .iter()
+                .zip(&idf)
+                .filter_map(|(term, idf)| {
+                    let tf = f64::from(*doc.terms.get(term)?);
+                    Some(idf * tf * (BM25_K1 + 1.0) / (tf + norm))
+                })
+                .sum();
+            (score > 0.0).then_some((score, doc))
+        })
+        .collect();
+    scored.sort_by(|(a, a_doc), (b, b_doc)| b.total_cmp(a).then_with(|| b_doc.timestamp.cmp(&a_doc.timestamp)));
+
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(score, doc)| SearchHit {
+            source: doc.source.clone(),
+            title: doc.title.clone(),
+            timestamp: doc.timestamp,
+            score,
+            snippet: snippet(&doc.text, query_terms),
+        })
+        .collect()
+}
+
+/// Splits text into lowercase search terms: words for alphabetic scripts, overlapping
+/// character pairs for CJK text (which has no spaces). Documents also index single CJK
+/// characters (`with_unigrams`) so one-character queries match.
+pub fn tokenize(text: &str, with_unigrams: bool) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut cjk_run: Vec<char> = Vec::new();
+
+    let flush_word = |word: &mut String, tokens: &mut Vec<String>| {
+        if !word.is_empty() {
+            tokens.push(std::mem::take(word));
+        }
+    };
+    let flush_cjk = |run: &mut Vec<char>, tokens: &mut Vec<String>| {
+        if run.len() == 1 || with_unigrams {
+            tokens.extend(run.iter().map(char::to_string));
+        }
+        tokens.extend(run.windows(2).map(|pair| pair.iter().collect::<String>()));
+        run.clear();
+    };
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            flush_word(&mut word, &mut tokens);
+            cjk_run.push(c);
+        } else if c.is_alphanumeric() {
+            flush_cjk(&mut cjk_run, &mut tokens);
+            word.extend(c.to_lowercase());
+        } else {
+            flush_word(&mut word, &mut tokens);
+            flush_cjk(&mut cjk_run, &mut tokens);
+        }
+    }
+    flush_word(&mut word, &mut tokens);
+    flush_cjk(&mut cjk_run, &mut tokens);
+    tokens
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'   // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{AC00}'..='\u{D7AF}' // Hangul syllables
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+        | '\u{20000}'..='\u{2FA1F}' // Extensions B-F, supplement
+    )
+}
+
+/// A window of `text` around the first match, split into plain and highlighted parts.
+/// Line breaks become spaces; `…` marks cut-off ends.
+fn snippet(text: &str, query_terms: &[String]) -> Vec<SnippetPart> {
+    let chars: Vec<char> = text.chars().map(|c| if c.is_whitespace() { ' ' } else { c }).collect();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+
+    // Words only match whole words, like the terms they were indexed as
+    let in_word = |c: char| c.is_alphanumeric() && !is_cjk(c);
+    let is_boundary = |i: usize| i == 0 || i == lower.len() || !in_word(lower[i - 1]) || !in_word(lower[i]);
+    let mut matches: Vec<(usize, usize)> = Vec::new();
+    for term in query_terms {
+        let term: Vec<char> = term.chars().collect();
+        if term.is_empty() || term.len() > lower.len() {
+            continue;
+        }
+        let whole_word = !term.iter().copied().any(is_cjk);
+        for start in 0..=lower.len() - term.len() {
+            let end = start + term.len();
+            if lower[start..end] == term[..] && (!whole_word || is_boundary(start) && is_boundary(end)) {
+                matches.push((start, end));
+            }
+        }
+    }
+    matches.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in matches {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    let first = merged.first().map_or(0, |&(start, _)| start);
+    let start = first.saturating_sub(SNIPPET_LEAD);
+    let end = (start + SNIPPET_LEN).min(chars.len());
+
+    let mut parts = Vec::new();
+    let mut push = |text: String, highlight: bool| {
+        if !text.is_empty() {
+            parts.push(SnippetPart { text, highlight });
+        }
+    };
+    let mut cursor = start;
+    let lead = if start > 0 { "…" } else { "" };
+    let mut plain = lead.to_string();
+    for (match_start, match_end) in merged.into_iter().filter(|&(s, e)| e > start && s < end) {
+        let (match_start, match_end) = (match_start.max(start), match_end.min(end));
+        plain.extend(&chars[cursor..match_start]);
+        push(std::mem::take(&mut plain), false);
+        push(chars[match_start..match_end].iter().collect(), true);
+        cursor = match_end;
+    }
+    plain.extend(&chars[cursor..end]);
+    if end < chars.len() {
+        plain.push('…');
+    }
+    push(plain, false);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::conversations::{append_message, ChatRole, StoredMessage};
+    use tempfile::TempDir;
+
+    fn say(dir: &Path, conversation: &str, id: &str, content: &str, timestamp: i64) {
+        let message = StoredMessage {
+            id: id.into(),
+            role: ChatRole::Assistant,
+            content: content.into(),
+            timestamp,
+            status: MessageStatus::Done,
+        };
+        append_message(dir, conversation, message).unwrap();
+    }
+
+    fn message_ids(hits: &[SearchHit]) -> Vec<String> {
+        hits.iter()
+            .map(|hit| match &hit.source {
+                HitSource::Message { message_id, .. } => message_id.clone(),
+                HitSource::Memory { path } => path.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize_words_and_cjk() {
+        assert_eq!(tokenize("Deploy-Script v2", false), vec!["deploy", "script", "v2"]);
+        assert_eq!(tokenize("部署脚本", false), vec!["部署", "署脚", "脚本"]);
+        assert_eq!(tokenize("用 rsync 部署", true), vec!["用", "rsync", "部", "署", "部署"]);
+    }
+
+    #[test]
+    fn test_search_ranks_and_highlights() {
+        let tmp = TempDir::new().unwrap();
+        say(tmp.path(), "c1", "m1", "The deployment script lives in scripts/deploy.sh and uses rsync.", 1);
+        say(tmp.path(), "c1", "m2", "Unrelated answer about the weather.", 2);
+        say(tmp.path(), "c2", "m3", "Deployment: run the deployment script, then check the deployment logs.", 3);
+        let index = HistoryIndex::default();
+
+        let hits = index.search(tmp.path(), None, "deployment script", 10).unwrap();
+
+        assert_eq!(message_ids(&hits), vec!["m3", "m1"]);
+        assert!(hits[0].score > hits[1].score);
+        assert_eq!(
+            hits[1].snippet,
+            vec![
+                SnippetPart { text: "The ".into(), highlight: false },
+                SnippetPart { text: "deployment".into(), highlight: true },
+                SnippetPart { text: " ".into(), highlight: false },
+                SnippetPart { text: "script".into(), highlight: true },
+                SnippetPart { text: " lives in scripts/deploy.sh and uses rsync.".into(), highlight: false },
+            ]
+        );
+        assert_eq!(index.search(tmp.path(), None, " ", 10).unwrap_err().code(), "invalid_input");
+    }
+
+    #[test]
+    fn test_search_cjk_and_follows_store_changes() {
+        let tmp = TempDir::new().unwrap();
+        say(tmp.path(), "c1", "m1", "上周说的部署脚本在 scripts 目录下", 1);
+        let index = HistoryIndex::default();
+
+        let hits = index.search(tmp.path(), None, "部署脚本", 10).unwrap();
+        assert_eq!(message_ids(&hits), vec!["m1"]);
+        assert!(hits[0].snippet.contains(&SnippetPart { text: "部署脚本".into(), highlight: true }));
+
+        say(tmp.path(), "c2", "m2", "部署完成", 2);
+        assert_eq!(message_ids(&index.search(tmp.path(), None, "部署", 10).unwrap()), vec!["m2", "m1"]);
+
+        conversations::delete_conversation(tmp.path(), "c1").unwrap();
+        assert_eq!(message_ids(&index.search(tmp.path(), None, "部署", 10).unwrap()), vec!["m2"]);
+    }
+
+    #[test]
+    fn test_search_includes_memory_when_asked() {
+        let tmp = TempDir::new().unwrap();
+        let (sessions, memory) = (tmp.path().join("sessions"), tmp.path().join("memory"));
+        fs::create_dir_all(memory.join("notes")).unwrap();
+        fs::write(memory.join("notes").join("deploy.md"), "# Deploy\nUse the blue-green deployment.").unwrap();
+        fs::write(memory.join("image.png"), "deployment").unwrap();
+        let index = HistoryIndex::default();
+
+        assert!(index.search(&sessions, None, "deployment", 10).unwrap().is_empty());
+        let hits = index.search(&sessions, Some(&memory), "deployment", 10).unwrap();
+        assert_eq!(message_ids(&hits), vec!["notes/deploy.md"]);
+        assert_eq!(hits[0].title, "deploy.md");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_search_skips_symlinked_memory_dirs() {
+        let tmp = TempDir::new().unwrap();
+        let (sessions, memory) = (tmp.path().join("sessions"), tmp.path().join("memory"));
+        fs::create_dir_all(memory.join("notes")).unwrap();
+        fs::write(memory.join("notes").join("deploy.md"), "Use the blue-green deployment.").unwrap();
+        std::os::unix::fs::symlink(&memory, memory.join("notes").join("loop")).unwrap();
+        let index = HistoryIndex::default();
+
+        let hits = index.search(&sessions, Some(&memory), "deployment", 10).unwrap();
+        assert_eq!(message_ids(&hits), vec!["notes/deploy.md"]);
+    }
+
+    #[test]
+    fn test_snippet_window_marks_cut_off_ends() {
+        let text = format!("{}needle{}", "a ".repeat(40), " b".repeat(100));
+        let parts = snippet(&text, &["needle".to_string()]);
+
+        assert!(parts[0].text.starts_with('…'));
+        assert_eq!(parts[1], SnippetPart { text: "needle".into(), highlight: true });
+        assert!(parts[2].text.ends_with('…'));
+        let len: usize = parts.iter().map(|part| part.text.chars().count()).sum();
+        assert_eq!(len, SNIPPET_LEN + 2);
+    }
+}
@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::error::EasyClawError;
+use crate::utils::fs::write_atomic;
+
+/// How EasyClaw runs the ZeroClaw gateway.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GatewayMode {
+    /// Use the OS service manager, falling back to `Embedded` if the service can't be installed or started.
+    #[default]
+    Auto,
+    /// Only use `zeroclaw service` (launchd/systemd).
+    Service,
+    /// Spawn `zeroclaw daemon` as a child of EasyClaw; it stops when the app exits.
+    Embedded,
+}
+
+/// EasyClaw client settings, stored as JSON in the platform config directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub gateway_mode: GatewayMode,
+    /// ZeroClaw root to use instead of ~/.zeroclaw (`ZEROCLAW_HOME` and portable mode take precedence).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zeroclaw_home: Option<PathBuf>,
+    /// Profile whose root is used (see `profiles`); None means the default profile.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+}
+
+/// Loads settings from `path`. A missing file yields the defaults.
+pub fn load_settings(path: &Path) -> Result<AppSettings, EasyClawError> {
+    if !path.exists() {
+        return Ok(AppSettings::default());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| EasyClawError::io(format!("Failed to read {}", path.display()), &e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| EasyClawError::Internal(format!("Failed to parse {}: {}", path.display(), e)))
+}
+
+/// Saves settings to `path`, creating its directory if needed.
+pub fn save_settings(path: &Path, settings: &AppSettings) -> Result<(), EasyClawError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| EasyClawError::io(format!("Failed to create {}", parent.display()), &e))?;
+    }
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| EasyClawError::Internal(format!("Failed to serialize settings: {}", e)))?;
+    write_atomic(path, content.as_bytes(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_settings_defaults_when_missing() {
+        let tmp = TempDir::new().unwrap();
+
+        let settings = load_settings(&tmp.path().join("settings.json")).unwrap();
+
+        assert_eq!(settings.gateway_mode, GatewayMode::Auto);
+    }
+
+    #[test]
+    fn test_save_and_load_settings_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("nested").join("settings.json");
+        let settings = AppSettings {
+            gateway_mode: GatewayMode::Embedded,
+            zeroclaw_home: Some(PathBuf::from("/mnt/data/zeroclaw")),
+            active_profile: Some("work".to_string()),
+        };
+
+        save_settings(&path, &settings).unwrap();
+
+        assert!(fs::read_to_string(&path).unwrap().contains("\"embedded\""));
+        assert_eq!(load_settings(&path).unwrap(), settings);
+    }
+}
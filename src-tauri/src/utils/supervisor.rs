@@ -0,0 +1,271 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::utils::health::HealthReport;
+
+/// Event emitted to the frontend whenever the supervised gateway changes state.
+pub const GATEWAY_STATE_EVENT: &str = "gateway://state-changed";
+
+/// How often the supervisor probes `/health`.
+pub const SUPERVISOR_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Restart timing and limits for the gateway supervisor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    /// Delay before the first restart; doubled for every further attempt.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Restarts attempted before giving up until the gateway is started again.
+    pub max_attempts: u32,
+    /// Failed probes tolerated right after start, while the gateway is still booting.
+    pub startup_grace_probes: u32,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            max_attempts: 5,
+            startup_grace_probes: 3,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Backoff before restart number `attempt` (1-based).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+/// Gateway state as seen by the supervisor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupervisedState {
+    /// Not supervised (never started, or stopped by the user).
+    Idle,
+    /// Started, waiting for the first healthy probe.
+    Starting,
+    Healthy,
+    /// Down; a restart is scheduled or running.
+    Restarting,
+    /// Down and `max_attempts` restarts didn't help.
+    GaveUp,
+}
+
+/// Payload of `gateway://state-changed`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GatewayStateChanged {
+    pub state: SupervisedState,
+    pub reason: String,
+    /// Restart attempt this event belongs to (0 if none).
+    pub attempt: u32,
+}
+
+/// What the supervisor loop should do after a probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupervisorAction {
+    Wait,
+    Restart { attempt: u32, delay: Duration },
+}
+
+/// Health-check bookkeeping for the gateway supervisor: turns a sequence of probe
+/// results into state-change events and restart decisions. Holds no handles, so the
+/// loop that drives it owns all side effects.
+#[derive(Debug, Clone)]
+pub struct Supervisor {
+    policy: RestartPolicy,
+    state: SupervisedState,
+    attempts: u32,
+    grace_failures: u32,
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new(RestartPolicy::default())
+    }
+}
+
+impl Supervisor {
+    pub fn new(policy: RestartPolicy) -> Self {
+        Self {
+            policy,
+            state: SupervisedState::Idle,
+            attempts: 0,
+            grace_failures: 0,
+        }
+    }
+
+    pub fn state(&self) -> SupervisedState {
+        self.state
+    }
+
+    /// Starts supervising, e.g. after the gateway was started. Resets the attempt count.
+    pub fn enable(&mut self) {
+        self.state = SupervisedState::Starting;
+        self.attempts = 0;
+        self.grace_failures = 0;
+    }
+
+    /// Records a successful restart: the relaunched gateway gets the startup grace
+    /// period again, while the attempt count is kept for the backoff.
+    pub fn relaunched(&mut self) {
+        if self.state == SupervisedState::Restarting {
+            self.state = SupervisedState::Starting;
+            self.grace_failures = 0;
+        }
+    }
+
+    /// Stops supervising, e.g. after the user stopped the gateway.
+    pub fn disable(&mut self) {
+        self.state = SupervisedState::Idle;
+    }
+
+    /// Records a probe result. Returns the event to emit, if the state changed,
+    /// and whether to restart the gateway.
+    pub fn observe(&mut self, report: &HealthReport) -> (Option<GatewayStateChanged>, SupervisorAction) {
+        if self.state == SupervisedState::Idle {
+            return (None, SupervisorAction::Wait);
+        }
+
+        if report.reachable {
+            let reason = match self.attempts {
+                0 => "Gateway is healthy".to_string(),
+                n => format!("Gateway recovered after {} restart attempt(s)", n),
+            };
+            self.attempts = 0;
+            self.grace_failures = 0;
+            return (self.transition(SupervisedState::Healthy, reason), SupervisorAction::Wait);
+        }
+
+        let error = report.error.as_deref().unwrap_or("health check failed");
+        match self.state {
+            SupervisedState::GaveUp => (None, SupervisorAction::Wait),
+            SupervisedState::Starting if self.grace_failures < self.policy.startup_grace_probes => {
+                self.grace_failures += 1;
+                (None, SupervisorAction::Wait)
+            }
+            _ if self.attempts >= self.policy.max_attempts => {
+                let reason = format!("Gave up after {} restart attempts: {}", self.attempts, error);
+                (self.transition(SupervisedState::GaveUp, reason), SupervisorAction::Wait)
+            }
+            _ => {
+                self.attempts += 1;
+                let delay = self.policy.backoff(self.attempts);
+                self.state = SupervisedState::Restarting;
+                let event = GatewayStateChanged {
+                    state: SupervisedState::Restarting,
+                    reason: format!("Gateway is down: {}", error),
+                    attempt: self.attempts,
+                };
+                (Some(event), SupervisorAction::Restart { attempt: self.attempts, delay })
+            }
+        }
+    }
+
+    fn transition(&mut self, state: SupervisedState, reason: String) -> Option<GatewayStateChanged> {
+        if self.state == state {
+            return None;
+        }
+        self.state = state;
+        Some(GatewayStateChanged { state, reason, attempt: self.attempts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn up() -> HealthReport {
+        HealthReport { reachable: true, ..HealthReport::default() }
+    }
+
+    fn down() -> HealthReport {
+        HealthReport { error: Some("connection refused".into()), ..HealthReport::default() }
+    }
+
+    fn policy() -> RestartPolicy {
+        RestartPolicy {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            max_attempts: 3,
+            startup_grace_probes: 1,
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = policy();
+        let delays: Vec<u64> = (1..=5).map(|n| policy.backoff(n).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 5, 5]);
+    }
+
+    #[test]
+    fn test_idle_supervisor_ignores_probes() {
+        let mut supervisor = Supervisor::new(policy());
+        assert_eq!(supervisor.observe(&down()), (None, SupervisorAction::Wait));
+        assert_eq!(supervisor.state(), SupervisedState::Idle);
+    }
+
+    #[test]
+    fn test_startup_grace_then_restart_with_backoff_then_give_up() {
+        let mut supervisor = Supervisor::new(policy());
+        supervisor.enable();
+
+        // Still booting: tolerated
+        assert_eq!(supervisor.observe(&down()), (None, SupervisorAction::Wait));
+
+        for attempt in 1..=3 {
+            let (event, action) = supervisor.observe(&down());
+            let event = event.unwrap();
+            assert_eq!(event.state, SupervisedState::Restarting);
+            assert_eq!(event.attempt, attempt);
+            assert!(event.reason.contains("connection refused"));
+            assert_eq!(action, SupervisorAction::Restart { attempt, delay: policy().backoff(attempt) });
+        }
+
+        let (event, action) = supervisor.observe(&down());
+        assert_eq!(event.unwrap().state, SupervisedState::GaveUp);
+        assert_eq!(action, SupervisorAction::Wait);
+        assert_eq!(supervisor.observe(&down()), (None, SupervisorAction::Wait));
+    }
+
+    #[test]
+    fn test_relaunched_gateway_gets_startup_grace() {
+        let mut supervisor = Supervisor::new(policy());
+        supervisor.enable();
+        supervisor.observe(&down());
+        let (_, action) = supervisor.observe(&down());
+        assert_eq!(action, SupervisorAction::Restart { attempt: 1, delay: Duration::from_secs(1) });
+
+        supervisor.relaunched();
+        assert_eq!(supervisor.state(), SupervisedState::Starting);
+        // Still booting after the restart: tolerated
+        assert_eq!(supervisor.observe(&down()), (None, SupervisorAction::Wait));
+
+        let (_, action) = supervisor.observe(&down());
+        assert_eq!(action, SupervisorAction::Restart { attempt: 2, delay: Duration::from_secs(2) });
+    }
+
+    #[test]
+    fn test_recovery_resets_attempts_and_reports_once() {
+        let mut supervisor = Supervisor::new(policy());
+        supervisor.enable();
+
+        assert_eq!(supervisor.observe(&up()).0.unwrap().state, SupervisedState::Healthy);
+        assert_eq!(supervisor.observe(&up()).0, None);
+
+        let (_, action) = supervisor.observe(&down());
+        assert_eq!(action, SupervisorAction::Restart { attempt: 1, delay: Duration::from_secs(1) });
+
+        let event = supervisor.observe(&up()).0.unwrap();
+        assert_eq!(event.state, SupervisedState::Healthy);
+        assert!(event.reason.contains("after 1 restart"));
+
+        let (_, action) = supervisor.observe(&down());
+        assert_eq!(action, SupervisorAction::Restart { attempt: 1, delay: Duration::from_secs(1) });
+    }
+}
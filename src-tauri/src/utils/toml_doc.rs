@@ -0,0 +1,323 @@
+use std::fs;
+use std::path::Path;
+
+use toml_edit::{DocumentMut, Item, Table, TableLike, Value};
+
+use crate::utils::error::EasyClawError;
+use crate::utils::fs::write_atomic;
+
+/// A format-preserving TOML document.
+///
+/// Wraps `toml_edit` so that values can be read and written by dotted key path
+/// (e.g. `gateway.port`) while user comments, whitespace and key ordering stay intact.
+/// Path segments are split on `.`, so keys that themselves contain dots are not supported.
+#[derive(Debug, Default, Clone)]
+pub struct TomlDocument {
+    doc: DocumentMut,
+}
+
+impl TomlDocument {
+    /// Parses a TOML document from a string.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let doc = content
+            .parse::<DocumentMut>()
+            .map_err(|e| format!("Failed to parse TOML: {}", e))?;
+        Ok(Self { doc })
+    }
+
+    /// Reads and parses a TOML file.
+    pub fn load(path: &Path) -> Result<Self, EasyClawError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| EasyClawError::io(format!("Failed to read {}", path.display()), &e))?;
+        Self::parse(&content)
+            .map_err(|e| EasyClawError::ConfigInvalid(format!("Failed to parse {}: {}", path.display(), e)))
+    }
+
+    /// Atomically writes the document to `path`. See `utils::fs::write_atomic` for `mode`.
+    pub fn save(&self, path: &Path, mode: Option<u32>) -> Result<(), EasyClawError> {
+        write_atomic(path, self.doc.to_string().as_bytes(), mode)
+    }
+
+    /// Returns the item at the given dotted key path, if present.
+    pub fn get(&self, key_path: &str) -> Option<&Item> {
+        let mut item = self.doc.as_item();
+        for segment in split_key_path(key_path).ok()? {
+            item = item.as_table_like()?.get(segment)?;
+        }
+        Some(item)
+    }
+
+    /// Sets the value at the given dotted key path.
+    ///
+    /// Missing parent tables are created as regular `[table]` sections. When the key
+    /// already exists its surrounding decoration (inline comments, spacing) is kept.
+    /// Returns true if the document changed.
+    pub fn set(&mut self, key_path: &str, value: impl Into<Value>) -> Result<bool, String> {
+        let segments = split_key_path(key_path)?;
+        let (key, parents) = segments.split_last().expect("key path has at least one segment");
+
+        let table = self.table_like_mut(parents, key_path)?;
+        let mut value = value.into();
+
+        match table.get_mut(key) {
+            Some(Item::Value(existing)) => {
+                if same_value(existing, &value) {
+                    return Ok(false);
+                }
+                *value.decor_mut() = existing.decor().clone();
+                *existing = value;
+            }
+            Some(item @ Item::ArrayOfTables(_)) if value.is_array() => {
+                // Callers that want to keep the `[[...]]` layout use `update_array_of_tables`
+                if item.clone().into_value().is_ok_and(|existing| same_data(&existing, &value)) {
+                    return Ok(false);
+                }
+                *item = Item::Value(value);
+            }
+            Some(Item::None) | None => {
+                table.insert(key, Item::Value(value));
+            }
+            Some(_) => {
+                return Err(format!("Cannot set `{}`: existing entry is a table", key_path));
+            }
+        }
+        Ok(true)
+    }
+
+    /// Makes sure a table exists at the given dotted key path, creating it (and missing
+    /// parents) as a `[table]` section. Returns true if the document changed.
+    pub fn insert_table(&mut self, key_path: &str) -> Result<bool, String> {
+        if self.get(key_path).is_some_and(Item::is_table_like) {
+            return Ok(false);
+        }
+        let segments = split_key_path(key_path)?;
+        self.table_like_mut(&segments, key_path)?;
+        Ok(true)
+    }
+
+    /// Removes the entry at the given dotted key path.
+    /// Returns true if an entry was removed.
+    pub fn remove(&mut self, key_path: &str) -> Result<bool, String> {
+        let segments = split_key_path(key_path)?;
+        let (key, parents) = segments.split_last().expect("key path has at least one segment");
+
+        let mut item = self.doc.as_item_mut();
+        for segment in parents {
+            match item.as_table_like_mut().and_then(|t| t.get_mut(segment)) {
+                Some(next) => item = next,
+                None => return Ok(false),
+            }
+        }
+        Ok(item
+            .as_table_like_mut()
+            .and_then(|t| t.remove(key))
+            .is_some())
+    }
+
+    /// Edits the `[[array]]` of tables at `key_path` in place so it holds `len` tables.
+    ///
+    /// `edit` is called with each table as a document of its own, so the tables keep
+    /// their headers, comments and layout. Tables past `len` are removed and missing
+    /// ones appended. Returns true if the document changed.
+    pub fn update_array_of_tables(
+        &mut self,
+        key_path: &str,
+        len: usize,
+        mut edit: impl FnMut(usize, &mut TomlDocument) -> Result<bool, String>,
+    ) -> Result<bool, String> {
+        let segments = split_key_path(key_path)?;
+        let (key, parents) = segments.split_last().expect("key path has at least one segment");
+        let tables = self
+            .table_like_mut(parents, key_path)?
+            .get_mut(key)
+            .and_then(Item::as_array_of_tables_mut)
+            .ok_or_else(|| format!("Cannot update `{}`: not an array of tables", key_path))?;
+
+        let mut changed = false;
+        while tables.len() > len {
+            tables.remove(tables.len() - 1);
+            changed = true;
+        }
+        for index in 0..len {
+            if index == tables.len() {
+                tables.push(Table::new());
+                changed = true;
+            }
+            let table = tables.get_mut(index).expect("index is within the array");
+            let mut doc = TomlDocument { doc: DocumentMut::from(std::mem::replace(table, Table::new())) };
+            let result = edit(index, &mut doc);
+            *table = std::mem::replace(doc.doc.as_table_mut(), Table::new());
+            changed |= result?;
+        }
+        Ok(changed)
+    }
+
+    /// Returns the keys of the table at `table_path` (the root table for `""`),
+    /// or an empty list if there is no such table.
+    pub fn table_keys(&self, table_path: &str) -> Vec<String> {
+        let item = if table_path.is_empty() {
+            Some(self.doc.as_item())
+        } else {
+            self.get(table_path)
+        };
+        item.and_then(Item::as_table_like)
+            .map(|t| t.iter().map(|(k, _)| k.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Walks `segments` from the root, creating missing tables along the way.
+    fn table_like_mut(
+        &mut self,
+        segments: &[&str],
+        key_path: &str,
+    ) -> Result<&mut dyn TableLike, String> {
+        let mut table: &mut dyn TableLike = self.doc.as_table_mut();
+        for segment in segments {
+            if table.get(segment).is_none() {
+                table.insert(segment, Item::Table(Table::new()));
+            }
+            table = table
+                .get_mut(segment)
+                .and_then(Item::as_table_like_mut)
+                .ok_or_else(|| {
+                    format!("Cannot set `{}`: `{}` is not a table", key_path, segment)
+                })?;
+        }
+        Ok(table)
+    }
+}
+
+impl std::fmt::Display for TomlDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.doc, f)
+    }
+}
+
+/// Splits a dotted key path into its segments, rejecting empty ones.
+pub fn split_key_path(key_path: &str) -> Result<Vec<&str>, String> {
+    let segments: Vec<&str> = key_path.split('.').map(str::trim).collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        return Err(format!("Invalid config key path: `{}`", key_path));
+    }
+    Ok(segments)
+}
+
+/// Compares two values ignoring their decoration (comments and whitespace).
+fn same_value(a: &Value, b: &Value) -> bool {
+    let mut a = a.clone();
+    let mut b = b.clone();
+    a.decor_mut().clear();
+    b.decor_mut().clear();
+    a.to_string() == b.to_string()
+}
+
+/// Compares two values by their data alone, ignoring all formatting.
+fn same_data(a: &Value, b: &Value) -> bool {
+    let parse = |value: &Value| format!("v = {}", value).parse::<toml::Table>().ok();
+    matches!((parse(a), parse(b)), (Some(a), Some(b)) if a == b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_existing_key_preserves_inline_comment() {
+        let mut doc = TomlDocument::parse("[gateway]\nport = 3000 # default port\n").unwrap();
+
+        assert!(doc.set("gateway.port", 18789).unwrap());
+
+        assert_eq!(doc.to_string(), "[gateway]\nport = 18789 # default port\n");
+    }
+
+    #[test]
+    fn test_set_handles_keys_without_spaces() {
+        let mut doc = TomlDocument::parse("[gateway]\nport=3000\n").unwrap();
+
+        doc.set("gateway.port", 18789).unwrap();
+
+        assert_eq!(doc.get("gateway.port").and_then(Item::as_integer), Some(18789));
+        assert!(!doc.to_string().contains("3000"));
+    }
+
+    #[test]
+    fn test_set_handles_dotted_keys() {
+        let mut doc = TomlDocument::parse("gateway.port = 3000\ngateway.host = \"127.0.0.1\"\n").unwrap();
+
+        doc.set("gateway.port", 18789).unwrap();
+        doc.set("gateway.require_pairing", false).unwrap();
+
+        let content = doc.to_string();
+        assert!(content.contains("gateway.port = 18789"));
+        assert!(content.contains("gateway.require_pairing = false"));
+        assert!(!content.contains("[gateway]"));
+    }
+
+    #[test]
+    fn test_set_creates_missing_table() {
+        let mut doc = TomlDocument::parse("# user config\n[memory]\nauto_save = true\n").unwrap();
+
+        doc.set("gateway.port", 18789).unwrap();
+
+        let content = doc.to_string();
+        assert!(content.starts_with("# user config\n[memory]\nauto_save = true\n"));
+        assert!(content.contains("[gateway]\nport = 18789\n"));
+    }
+
+    #[test]
+    fn test_set_same_value_reports_unchanged() {
+        let mut doc = TomlDocument::parse("[gateway]\nrequire_pairing = false\n").unwrap();
+
+        assert!(!doc.set("gateway.require_pairing", false).unwrap());
+    }
+
+    #[test]
+    fn test_insert_table_keeps_existing_tables() {
+        let mut doc = TomlDocument::parse("[gateway]\nport = 3000\n").unwrap();
+
+        assert!(!doc.insert_table("gateway").unwrap());
+        assert!(doc.insert_table("channels_config.telegram").unwrap());
+        assert!(doc.to_string().contains("[channels_config.telegram]\n"));
+        assert!(doc.to_string().starts_with("[gateway]\nport = 3000\n"));
+    }
+
+    #[test]
+    fn test_set_rejects_non_table_parent() {
+        let mut doc = TomlDocument::parse("gateway = 1\n").unwrap();
+
+        assert!(doc.set("gateway.port", 18789).is_err());
+    }
+
+    #[test]
+    fn test_set_preserves_ordering_and_other_sections() {
+        let original = "[memory]\n# keep me\nauto_save = true\n\n[gateway]\nhost = \"127.0.0.1\"\nport = 3000\n\n[channels_config]\ncli = true\n";
+        let mut doc = TomlDocument::parse(original).unwrap();
+
+        doc.set("gateway.port", 18789).unwrap();
+
+        assert_eq!(doc.to_string(), original.replace("port = 3000", "port = 18789"));
+    }
+
+    #[test]
+    fn test_remove_and_table_keys() {
+        let mut doc = TomlDocument::parse("title = \"x\"\n[gateway]\nport = 3000\nhost = \"127.0.0.1\"\n").unwrap();
+
+        assert_eq!(doc.table_keys(""), vec!["title", "gateway"]);
+        assert_eq!(doc.table_keys("gateway"), vec!["port", "host"]);
+
+        assert!(doc.remove("gateway.port").unwrap());
+        assert!(!doc.remove("gateway.port").unwrap());
+        assert!(!doc.remove("missing.port").unwrap());
+        assert_eq!(doc.to_string(), "title = \"x\"\n[gateway]\nhost = \"127.0.0.1\"\n");
+    }
+
+    #[test]
+    fn test_set_array_of_tables_to_same_data_reports_unchanged() {
+        let original = "[[fallbacks]]\nprovider = \"openai\" # first\n\n[[fallbacks]]\nprovider = \"ollama\"\n";
+        let mut doc = TomlDocument::parse(original).unwrap();
+        let same: Value = "[{ provider = \"openai\" }, { provider = \"ollama\" }]".parse().unwrap();
+
+        assert!(!doc.set("fallbacks", same).unwrap());
+        assert_eq!(doc.to_string(), original);
+    }
+}
@@ -0,0 +1,322 @@
+use std::net::IpAddr;
+
+use serde::Serialize;
+use toml::{Table, Value};
+
+/// Memory backends understood by ZeroClaw.
+const MEMORY_BACKENDS: &[&str] = &["sqlite", "markdown", "none"];
+
+/// Autonomy levels understood by ZeroClaw.
+const AUTONOMY_LEVELS: &[&str] = &["readonly", "supervised", "full"];
+
+/// Providers that run locally and therefore don't need an API key.
+const LOCAL_PROVIDERS: &[&str] = &["ollama"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single validation finding, addressed by dotted key path so the UI can
+/// highlight the offending field. An empty path refers to the whole document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub path: String,
+    pub message: String,
+    pub severity: Severity,
+}
+
+impl Diagnostic {
+    fn error(path: &str, message: impl Into<String>) -> Self {
+        Self { path: path.to_string(), message: message.into(), severity: Severity::Error }
+    }
+
+    fn warning(path: &str, message: impl Into<String>) -> Self {
+        Self { path: path.to_string(), message: message.into(), severity: Severity::Warning }
+    }
+}
+
+/// Returns true if any diagnostic is an error.
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
+
+/// Formats error diagnostics into a single message, e.g. for refusing a save.
+pub fn describe_errors(diagnostics: &[Diagnostic]) -> String {
+    let errors: Vec<String> = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| if d.path.is_empty() { d.message.clone() } else { format!("{}: {}", d.path, d.message) })
+        .collect();
+    format!("Config is invalid: {}", errors.join("; "))
+}
+
+/// Parses and validates config.toml content.
+pub fn validate_str(content: &str) -> Vec<Diagnostic> {
+    match toml::from_str::<Table>(content) {
+        Ok(table) => validate(&table),
+        Err(e) => vec![Diagnostic::error("", format!("Invalid TOML: {}", e.message()))],
+    }
+}
+
+/// Validates a parsed ZeroClaw config document.
+pub fn validate(config: &Table) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    validate_provider(config, &mut diagnostics);
+    validate_gateway(config, &mut diagnostics);
+    validate_enum(config, "memory", "backend", MEMORY_BACKENDS, &mut diagnostics);
+    validate_enum(config, "autonomy", "level", AUTONOMY_LEVELS, &mut diagnostics);
+    diagnostics
+}
+
+fn validate_provider(config: &Table, diagnostics: &mut Vec<Diagnostic>) {
+    let provider = expect_str(config, "", "default_provider", diagnostics);
+    match provider {
+        None if !config.contains_key("default_provider") => {
+            diagnostics.push(Diagnostic::error("default_provider", "A default provider is required"));
+        }
+        Some("") => {
+            diagnostics.push(Diagnostic::error("default_provider", "Default provider must not be empty"));
+        }
+        _ => {}
+    }
+
+    match expect_str(config, "", "default_model", diagnostics) {
+        None if !config.contains_key("default_model") => {
+            diagnostics.push(Diagnostic::error("default_model", "A default model is required"));
+        }
+        Some("") => {
+            diagnostics.push(Diagnostic::error("default_model", "Default model must not be empty"));
+        }
+        _ => {}
+    }
+
+    let api_key = expect_str(config, "", "api_key", diagnostics).unwrap_or("");
+    if let Some(provider) = provider {
+        if api_key.is_empty() && !provider.is_empty() && !LOCAL_PROVIDERS.contains(&provider) {
+            diagnostics.push(Diagnostic::warning(
+                "api_key",
+                format!("No API key set; provider `{}` will likely reject requests", provider),
+            ));
+        }
+    }
+
+    if let Some(value) = config.get("default_temperature") {
+        match value.as_float().or_else(|| value.as_integer().map(|i| i as f64)) {
+            Some(t) if !(0.0..=2.0).contains(&t) => {
+                diagnostics.push(Diagnostic::error("default_temperature", "Temperature must be between 0.0 and 2.0"));
+            }
+            Some(_) => {}
+            None => diagnostics.push(type_error("default_temperature", "a number", value)),
+        }
+    }
+}
+
+fn validate_gateway(config: &Table, diagnostics: &mut Vec<Diagnostic>) {
+    let gateway = match expect_table(config, "gateway", diagnostics) {
+        Some(gateway) => gateway,
+        None => return,
+    };
+
+    if let Some(value) = gateway.get("port") {
+        match value.as_integer() {
+            Some(port) if !(1..=65535).contains(&port) => {
+                diagnostics.push(Diagnostic::error("gateway.port", "Port must be between 1 and 65535"));
+            }
+            Some(port) if port < 1024 => {
+                diagnostics.push(Diagnostic::warning(
+                    "gateway.port",
+                    "Ports below 1024 usually require administrator privileges",
+                ));
+            }
+            Some(_) => {}
+            None => diagnostics.push(type_error("gateway.port", "an integer", value)),
+        }
+    }
+
+    let allow_public_bind = expect_bool(gateway, "gateway", "allow_public_bind", diagnostics).unwrap_or(false);
+    expect_bool(gateway, "gateway", "require_pairing", diagnostics);
+
+    if let Some(host) = expect_str(gateway, "gateway", "host", diagnostics) {
+        match host.parse::<IpAddr>() {
+            Ok(ip) if !ip.is_loopback() && !allow_public_bind => {
+                diagnostics.push(Diagnostic::error(
+                    "gateway.host",
+                    "Binding to a non-loopback address requires gateway.allow_public_bind = true",
+                ));
+            }
+            Ok(_) => {}
+            Err(_) if host == "localhost" => {}
+            Err(_) => {
+                diagnostics.push(Diagnostic::error(
+                    "gateway.host",
+                    format!("`{}` is not a valid IP address", host),
+                ));
+            }
+        }
+    }
+}
+
+fn validate_enum(
+    config: &Table,
+    section: &str,
+    key: &str,
+    allowed: &[&str],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let table = match expect_table(config, section, diagnostics) {
+        Some(table) => table,
+        None => return,
+    };
+    if let Some(value) = expect_str(table, section, key, diagnostics) {
+        if !allowed.contains(&value) {
+            diagnostics.push(Diagnostic::error(
+                &format!("{}.{}", section, key),
+                format!("Unknown value `{}`, expected one of: {}", value, allowed.join(", ")),
+            ));
+        }
+    }
+}
+
+fn expect_table<'a>(config: &'a Table, key: &str, diagnostics: &mut Vec<Diagnostic>) -> Option<&'a Table> {
+    let value = config.get(key)?;
+    let table = value.as_table();
+    if table.is_none() {
+        diagnostics.push(type_error(key, "a table", value));
+    }
+    table
+}
+
+fn expect_str<'a>(
+    table: &'a Table,
+    section: &str,
+    key: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<&'a str> {
+    let value = table.get(key)?;
+    let s = value.as_str();
+    if s.is_none() {
+        diagnostics.push(type_error(&key_path(section, key), "a string", value));
+    }
+    s
+}
+
+fn expect_bool(table: &Table, section: &str, key: &str, diagnostics: &mut Vec<Diagnostic>) -> Option<bool> {
+    let value = table.get(key)?;
+    let b = value.as_bool();
+    if b.is_none() {
+        diagnostics.push(type_error(&key_path(section, key), "a boolean", value));
+    }
+    b
+}
+
+fn type_error(path: &str, expected: &str, found: &Value) -> Diagnostic {
+    Diagnostic::error(path, format!("Expected {}, found {}", expected, found.type_str()))
+}
+
+fn key_path(section: &str, key: &str) -> String {
+    if section.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", section, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID: &str = r#"api_key = "sk-test"
+default_provider = "openrouter"
+default_model = "anthropic/claude-sonnet-4"
+default_temperature = 0.7
+
+[memory]
+backend = "sqlite"
+
+[autonomy]
+level = "supervised"
+
+[gateway]
+port = 18789
+host = "127.0.0.1"
+require_pairing = false
+"#;
+
+    fn paths(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_valid_config_has_no_diagnostics() {
+        assert!(validate_str(VALID).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_toml_is_reported_on_document() {
+        let diagnostics = validate_str("[gateway\nport = 1");
+        assert_eq!(paths(&diagnostics), vec![""]);
+        assert!(has_errors(&diagnostics));
+    }
+
+    #[test]
+    fn test_port_out_of_range_and_wrong_type() {
+        let diagnostics = validate_str(&VALID.replace("port = 18789", "port = 70000"));
+        assert_eq!(paths(&diagnostics), vec!["gateway.port"]);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+
+        let diagnostics = validate_str(&VALID.replace("port = 18789", "port = \"18789\""));
+        assert_eq!(paths(&diagnostics), vec!["gateway.port"]);
+
+        let diagnostics = validate_str(&VALID.replace("port = 18789", "port = 80"));
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_host_must_be_valid_address() {
+        let diagnostics = validate_str(&VALID.replace("127.0.0.1", "not an address"));
+        assert_eq!(paths(&diagnostics), vec!["gateway.host"]);
+
+        let diagnostics = validate_str(&VALID.replace("127.0.0.1", "0.0.0.0"));
+        assert_eq!(paths(&diagnostics), vec!["gateway.host"]);
+
+        let public = VALID.replace("127.0.0.1", "0.0.0.0") + "allow_public_bind = true\n";
+        assert!(validate_str(&public).is_empty());
+        assert!(validate_str(&VALID.replace("127.0.0.1", "localhost")).is_empty());
+    }
+
+    #[test]
+    fn test_required_provider_fields() {
+        let content = VALID
+            .replace("default_provider = \"openrouter\"\n", "")
+            .replace("default_model = \"anthropic/claude-sonnet-4\"", "default_model = \"\"");
+        let diagnostics = validate_str(&content);
+        assert_eq!(paths(&diagnostics), vec!["default_provider", "default_model"]);
+    }
+
+    #[test]
+    fn test_missing_api_key_is_a_warning_except_for_local_providers() {
+        let content = VALID.replace("api_key = \"sk-test\"\n", "");
+        let diagnostics = validate_str(&content);
+        assert_eq!(paths(&diagnostics), vec!["api_key"]);
+        assert!(!has_errors(&diagnostics));
+
+        assert!(validate_str(&content.replace("openrouter", "ollama")).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_enum_values() {
+        let content = VALID.replace("\"sqlite\"", "\"redis\"").replace("\"supervised\"", "\"yolo\"");
+        let diagnostics = validate_str(&content);
+        assert_eq!(paths(&diagnostics), vec!["memory.backend", "autonomy.level"]);
+        assert!(diagnostics[0].message.contains("sqlite, markdown, none"));
+    }
+
+    #[test]
+    fn test_describe_errors_lists_error_paths() {
+        let diagnostics = validate_str(&VALID.replace("port = 18789", "port = 0"));
+        assert_eq!(describe_errors(&diagnostics), "Config is invalid: gateway.port: Port must be between 1 and 65535");
+    }
+}
@@ -0,0 +1,82 @@
+use std::cmp::Ordering;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::utils::process::{discard_lines, ProcessOptions, ProcessRunner};
+
+/// Timeout for `zeroclaw --version`.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Runs `<bin> --version` and extracts the version number (e.g. `0.1.0`).
+/// Returns None if the binary can't be run or prints no version.
+pub async fn query_version(runner: &dyn ProcessRunner, bin_path: &Path) -> Option<String> {
+    let options = ProcessOptions::with_timeout(VERSION_TIMEOUT);
+    let output = runner.run(bin_path, &["--version"], &options, &discard_lines).await.ok()?;
+    parse_version(&output.stdout)
+}
+
+/// Finds the first version-looking token in `--version` output, e.g. `zeroclaw v0.2.1`.
+pub fn parse_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .map(|token| token.trim_start_matches('v'))
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))
+        .map(String::from)
+}
+
+/// Compares two dotted versions numerically (`0.10.0` > `0.9.1`). Missing components
+/// count as 0, and a pre-release (`0.2.0-beta`) sorts before its release.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_core, a_pre) = split_pre_release(a);
+    let (b_core, b_pre) = split_pre_release(b);
+
+    let a_parts: Vec<u64> = numeric_parts(a_core);
+    let b_parts: Vec<u64> = numeric_parts(b_core);
+    let len = a_parts.len().max(b_parts.len());
+    let core = (0..len)
+        .map(|i| a_parts.get(i).unwrap_or(&0).cmp(b_parts.get(i).unwrap_or(&0)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal);
+
+    core.then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a_pre), Some(b_pre)) => a_pre.cmp(b_pre),
+    })
+}
+
+fn split_pre_release(version: &str) -> (&str, Option<&str>) {
+    // Build metadata (`+abc`) doesn't affect ordering
+    let version = version.split('+').next().unwrap_or(version);
+    match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    }
+}
+
+fn numeric_parts(core: &str) -> Vec<u64> {
+    core.split('.').map(|part| part.parse().unwrap_or(0)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("zeroclaw 0.1.0\n").as_deref(), Some("0.1.0"));
+        assert_eq!(parse_version("zeroclaw v0.2.1-beta").as_deref(), Some("0.2.1-beta"));
+        assert_eq!(parse_version("zeroclaw"), None);
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("0.10.0", "0.9.1"), Ordering::Greater);
+        assert_eq!(compare_versions("0.1", "0.1.0"), Ordering::Equal);
+        assert_eq!(compare_versions("0.2.0-beta", "0.2.0"), Ordering::Less);
+        assert_eq!(compare_versions("0.2.0-beta.2", "0.2.0-beta.1"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0.0+build5", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("0.1.0", "0.1.1"), Ordering::Less);
+    }
+}
@@ -1,6 +1,6 @@
 import { ref } from "vue";
-
-const GATEWAY_URL = "http://localhost:18789";
+import { invoke, Channel } from "@tauri-apps/api/core";
+import { describeError, describeHttpStatus } from "../utils/errors";
 
 export interface ChatMessage {
     id: string;
@@ -10,44 +10,108 @@ export interface ChatMessage {
     status: "sending" | "streaming" | "done" | "error";
 }
 
-function generateId(): string {
-    return `${Date.now()}-${Math.random().toString(36).slice(2, 9)}`;
+/** Progress of a reply, streamed by `send_chat_message`. */
+export type ChatEvent = { event: "delta"; text: string };
+
+/** A saved conversation, as returned by `load_conversation`. */
+export interface Conversation {
+    id: string;
+    title: string;
+    created_at: string;
+    updated_at: string;
+    messages: Array<Pick<ChatMessage, "id" | "role" | "content" | "timestamp"> & { status: "done" | "error" }>;
 }
 
-function formatNetworkError(err: unknown): string {
-    const raw = err instanceof Error ? err.message : String(err);
-    return `无法连接到服务，请检查 Gateway 是否已启动。\n原始错误：${raw}`;
+/** A saved conversation without its messages, as returned by `list_conversations`. */
+export interface ConversationSummary {
+    id: string;
+    title: string;
+    created_at: string;
+    updated_at: string;
+    message_count: number;
 }
 
-function formatHttpError(status: number, serverMsg?: string): string {
-    if (serverMsg) return serverMsg;
-    const statusMap: Record<number, string> = {
-        400: `请求参数错误 (${status})`,
-        401: `认证失败，请检查配置 (${status})`,
-        403: `访问被拒绝 (${status})`,
-        404: `接口不存在，请检查 Gateway 版本 (${status})`,
-        429: `请求过于频繁，请稍后重试 (${status})`,
-        500: `服务内部错误 (${status})`,
-        502: `网关错误 (${status})`,
-        503: `服务暂时不可用 (${status})`,
-    };
-    return statusMap[status] ?? `请求失败 (${status})`;
+/** What `send_chat_message` resolves with once the reply is complete. */
+export interface ChatReply {
+    content: string;
+    streamed: boolean;
+    cancelled: boolean;
+}
+
+function generateId(): string {
+    return `${Date.now()}-${Math.random().toString(36).slice(2, 9)}`;
+}
+
+/** Turns a rejected `send_chat_message` into the text shown in the chat. */
+function formatChatError(err: unknown): string {
+    const { code, message, detail, status } = describeError(err);
+    if (code === "gateway_rejected") {
+        // Without a message of the gateway's own, the HTTP status says what went wrong
+        if (status !== null) return describeHttpStatus(status);
+        // The gateway's own message (e.g. "Unauthorized: pairing required") is the most useful
+        if (detail) return detail;
+    }
+    const raw = detail ? `${message}: ${detail}` : message;
+    if (code === "gateway_unreachable" || code === "timeout") {
+        return `无法连接到服务，请检查 Gateway 是否已启动。\n原始错误：${raw}`;
+    }
+    return raw;
 }
 
 export function useChat() {
     const messages = ref<ChatMessage[]>([]);
     const isLoading = ref(false);
     const error = ref<string | null>(null);
-    let abortController: AbortController | null = null;
+    const conversationId = ref<string | null>(null);
+    let activeRequestId: string | null = null;
+    // Appends are chained so messages are saved in the order they were shown
+    let saving: Promise<void> = Promise.resolve();
+
+    /** Saves a finished message to the current conversation, starting one if needed. */
+    function persist(message: ChatMessage): void {
+        if (!message.content) return;
+        if (!conversationId.value) conversationId.value = generateId();
+        const id = conversationId.value;
+        const stored = {
+            id: message.id,
+            role: message.role,
+            content: message.content,
+            timestamp: message.timestamp,
+            status: message.status === "error" ? "error" : "done",
+        };
+        saving = saving
+            .then(() => invoke<ConversationSummary>("append_message", { id, message: stored }))
+            .then(() => undefined)
+            .catch((err) => {
+                console.error("append_message failed:", err);
+            });
+    }
+
+    /** Shows the most recently changed conversation again, e.g. when the chat view is reopened. */
+    async function restoreConversation(): Promise<void> {
+        if (messages.value.length > 0) return;
+        try {
+            const [latest] = await invoke<ConversationSummary[]>("list_conversations");
+            if (!latest) return;
+            const conversation = await invoke<Conversation>("load_conversation", { id: latest.id });
+            if (messages.value.length > 0) return;
+            conversationId.value = conversation.id;
+            messages.value = conversation.messages.map((message) => ({ ...message }));
+        } catch (err: unknown) {
+            console.error("Failed to restore conversation:", err);
+        }
+    }
 
     function pushErrorAssistant(errorMsg: string): void {
-        messages.value.push({
+        const message: ChatMessage = {
             id: generateId(),
             role: "assistant",
             content: errorMsg,
             timestamp: Date.now(),
             status: "error",
-        });
+        };
+        messages.value.push(message);
+        persist(message);
     }
 
     async function sendMessage(content: string): Promise<void> {
@@ -64,101 +128,111 @@ export function useChat() {
             status: "done",
         };
         messages.value.push(userMessage);
+        persist(userMessage);
         isLoading.value = true;
 
-        abortController = new AbortController();
+        const requestId = generateId();
+        activeRequestId = requestId;
+        let assistantIndex: number | null = null;
+
+        const onEvent = new Channel<ChatEvent>();
+        onEvent.onmessage = (event) => {
+            if (activeRequestId !== requestId || event.event !== "delta") return;
+            if (assistantIndex === null) {
+                assistantIndex = messages.value.length;
+                messages.value.push({
+                    id: generateId(),
+                    role: "assistant",
+                    content: "",
+                    timestamp: Date.now(),
+                    status: "streaming",
+                });
+            }
+            messages.value[assistantIndex].content += event.text;
+        };
 
-        let response: Response;
+        let reply: ChatReply;
         try {
-            response = await fetch(`${GATEWAY_URL}/webhook`, {
-                method: "POST",
-                headers: { "Content-Type": "application/json" },
-                body: JSON.stringify({
-                    message: trimmed,
-                }),
-                signal: abortController.signal,
-            });
+            reply = await invoke<ChatReply>("send_chat_message", { requestId, message: trimmed, onEvent });
         } catch (err: unknown) {
-            if (err instanceof DOMException && err.name === "AbortError") {
-                isLoading.value = false;
-                return;
-            }
-            const errorMsg = formatNetworkError(err);
+            if (activeRequestId !== requestId) return;
+            activeRequestId = null;
+            const errorMsg = formatChatError(err);
             error.value = errorMsg;
-            pushErrorAssistant(errorMsg);
-            isLoading.value = false;
-            return;
-        }
-
-        if (!response.ok) {
-            let serverMsg: string | undefined;
-            try {
-                const body = await response.json();
-                if (body.error) {
-                    serverMsg = body.error;
-                }
-            } catch {
-                // Use default error message
+            if (assistantIndex === null) {
+                pushErrorAssistant(errorMsg);
+            } else {
+                messages.value[assistantIndex].content = errorMsg;
+                messages.value[assistantIndex].status = "error";
+                persist(messages.value[assistantIndex]);
             }
-            const errorMsg = formatHttpError(response.status, serverMsg);
-            error.value = errorMsg;
-            pushErrorAssistant(errorMsg);
             isLoading.value = false;
             return;
         }
 
-        const assistantMessage: ChatMessage = {
-            id: generateId(),
-            role: "assistant",
-            content: "",
-            timestamp: Date.now(),
-            status: "streaming",
-        };
-        messages.value.push(assistantMessage);
-
-        try {
-            const body = await response.json();
-            assistantMessage.content = body.reply ?? body.response ?? body.content ?? JSON.stringify(body);
-            assistantMessage.status = "done";
-        } catch (err: unknown) {
-            if (err instanceof DOMException && err.name === "AbortError") {
-                assistantMessage.status = "done";
-            } else {
-                const errorMsg = err instanceof Error ? err.message : String(err);
-                error.value = errorMsg;
-                assistantMessage.status = "error";
-                assistantMessage.content = errorMsg;
-            }
+        // A reply that arrives after abortResponse() was already finalized there
+        if (activeRequestId !== requestId) return;
+        activeRequestId = null;
+        if (assistantIndex === null) {
+            assistantIndex = messages.value.length;
+            messages.value.push({
+                id: generateId(),
+                role: "assistant",
+                content: reply.content,
+                timestamp: Date.now(),
+                status: "done",
+            });
+        } else {
+            messages.value[assistantIndex].content = reply.content;
+            messages.value[assistantIndex].status = "done";
         }
-
+        persist(messages.value[assistantIndex]);
         isLoading.value = false;
     }
 
     function abortResponse(): void {
-        if (abortController) {
-            abortController.abort();
-            abortController = null;
+        if (activeRequestId) {
+            // The backend stops reading the reply; what arrived so far stays in the chat
+            invoke<boolean>("cancel_chat_message", { requestId: activeRequestId }).catch((err) => {
+                console.error("cancel_chat_message failed:", err);
+            });
+            activeRequestId = null;
         }
-        // Mark any streaming messages as done
+        // Mark any streaming messages as done and keep what arrived
         for (const msg of messages.value) {
             if (msg.status === "streaming") {
                 msg.status = "done";
+                persist(msg);
             }
         }
         isLoading.value = false;
     }
 
+    /** Clears the chat and starts a new conversation; the saved one is kept. */
     function clearMessages(): void {
+        abortResponse();
+        conversationId.value = null;
         messages.value = [];
         error.value = null;
     }
 
+    /** Deletes a saved conversation; deleting the current one also clears the chat. */
+    async function deleteConversation(id: string): Promise<void> {
+        if (id === conversationId.value) clearMessages();
+        // Wait for pending appends so they don't recreate the deleted file
+        await saving;
+        await invoke<void>("delete_conversation", { id });
+    }
+
     return {
         messages,
         isLoading,
         error,
+        conversationId,
         sendMessage,
         abortResponse,
         clearMessages,
+        deleteConversation,
+        restoreConversation,
     };
 }
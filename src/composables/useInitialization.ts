@@ -1,5 +1,7 @@
-import { ref } from "vue";
+import { ref, computed } from "vue";
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { describeError, type EasyClawErrorCode } from "../utils/errors";
 
 export type InitStatus = "idle" | "checking" | "initializing" | "starting_gateway" | "success" | "error";
 
@@ -8,22 +10,67 @@ export interface InitState {
   progress: number;
   message: string;
   error: string | null;
+  /** Stable code of the failure, when the backend reported a typed error. */
+  errorCode: EasyClawErrorCode | null;
+  /** Suggested next step for the failure, if any. */
+  remedy: string | null;
 }
 
+/** One line of `zeroclaw onboard` / `zeroclaw service ...` output, streamed while it runs. */
+export interface ProcessOutputEvent {
+  step: string;
+  stream: "stdout" | "stderr";
+  line: string;
+}
+
+/** What `sync_zeroclaw_binary` did with the bundled ZeroClaw binary. */
+export type InstallOutcome =
+  | { action: "installed"; version: string | null }
+  | { action: "upgraded"; from: string | null; to: string }
+  | { action: "up_to_date"; installed: string | null; bundled: string | null };
+
+const MAX_OUTPUT_LINES = 200;
+
 export function useInitialization() {
   const state = ref<InitState>({
     status: "idle",
     progress: 0,
     message: "",
     error: null,
+    errorCode: null,
+    remedy: null,
   });
 
+  const output = ref<ProcessOutputEvent[]>([]);
+  const lastOutputLine = computed(() => output.value.at(-1)?.line ?? null);
+
   async function checkAndInitialize() {
+    output.value = [];
+    const unlisten: UnlistenFn | null = await listen<ProcessOutputEvent>(
+      "process://output",
+      (event) => {
+        output.value.push(event.payload);
+        if (output.value.length > MAX_OUTPUT_LINES) {
+          output.value.shift();
+        }
+      }
+    ).catch(() => null);
+
+    try {
+      await runInitialization();
+    } finally {
+      unlisten?.();
+    }
+  }
+
+  async function runInitialization() {
     state.value = {
       status: "checking",
       progress: 10,
       message: "正在检查初始化状态...",
       error: null,
+      errorCode: null,
+      remedy: null,
     };
 
     const isInitialized = await invoke<boolean>("check_initialized").catch(
@@ -34,11 +81,26 @@ export function useInitialization() {
     );
 
     if (isInitialized) {
+      // Pick up a newer bundled ZeroClaw after an app update (non-critical)
+      const install = await invoke<InstallOutcome>("sync_zeroclaw_binary").catch(
+        (err) => {
+          console.error("sync_zeroclaw_binary failed (non-critical):", err);
+          return null;
+        }
+      );
+      if (install?.action === "upgraded") {
+        await invoke<string>("restart_gateway").catch((err) => {
+          console.error("Gateway restart after upgrade failed (non-critical):", err);
+        });
+      }
+
       state.value = {
         status: "success",
         progress: 100,
         message: "就绪",
         error: null,
+        errorCode: null,
+        remedy: null,
       };
       return;
     }
@@ -49,15 +111,20 @@ export function useInitialization() {
       progress: 30,
       message: "正在初始化配置文件...",
       error: null,
+      errorCode: null,
+      remedy: null,
     };
 
     const initResult = await invoke<string>("initialize_zeroclaw").catch(
       (err) => {
+        const { code, message, detail, remedy } = describeError(err);
         state.value = {
           status: "error",
           progress: 30,
           message: "初始化失败",
-          error: String(err),
+          error: detail ? `${message}: ${detail}` : message,
+          errorCode: code,
+          remedy,
         };
         return null;
       }
@@ -71,6 +138,8 @@ export function useInitialization() {
       progress: 80,
       message: "正在启动服务...",
       error: null,
+      errorCode: null,
+      remedy: null,
     };
 
     await invoke<string>("start_gateway").catch((err) => {
@@ -82,6 +151,8 @@ export function useInitialization() {
       progress: 100,
       message: "初始化完成",
       error: null,
+      errorCode: null,
+      remedy: null,
     };
   }
 
@@ -91,6 +162,8 @@ export function useInitialization() {
 
   return {
     state,
+    output,
+    lastOutputLine,
     checkAndInitialize,
     retry,
   };
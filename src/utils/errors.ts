@@ -0,0 +1,96 @@
+/** Stable codes of the errors returned by EasyClaw's Tauri commands. */
+export type EasyClawErrorCode =
+  | "binary_missing"
+  | "unsupported_platform"
+  | "integrity_check_failed"
+  | "onboard_failed"
+  | "service_failed"
+  | "gateway_unreachable"
+  | "gateway_rejected"
+  | "permission_denied"
+  | "port_in_use"
+  | "config_invalid"
+  | "timeout"
+  | "not_found"
+  | "invalid_input"
+  | "internal";
+
+/** Error payload of a rejected `invoke`. */
+export interface EasyClawError {
+  code: EasyClawErrorCode;
+  /** English summary; the UI shows its own text for `code`. */
+  message: string;
+  /** Underlying error text, e.g. a path or process output. */
+  detail: string;
+  /** English suggestion; the UI shows its own text for `code`. */
+  remedy: string | null;
+  /** HTTP status of a `gateway_rejected` error the gateway gave no message for. */
+  status?: number;
+}
+
+/** A rejected `invoke`, described for display. */
+export interface ErrorDescription {
+  code: EasyClawErrorCode | null;
+  message: string;
+  detail: string | null;
+  remedy: string | null;
+  status: number | null;
+}
+
+const MESSAGES: Record<EasyClawErrorCode, { message: string; remedy: string | null }> = {
+  binary_missing: { message: "未找到 ZeroClaw 程序", remedy: "请重新运行初始化以安装 ZeroClaw" },
+  unsupported_platform: { message: "当前系统没有可用的 ZeroClaw 版本", remedy: "请下载与您的系统和架构匹配的 EasyClaw 安装包" },
+  integrity_check_failed: { message: "ZeroClaw 程序完整性校验失败", remedy: "内置程序已损坏或被修改，请重新安装 EasyClaw" },
+  onboard_failed: { message: "ZeroClaw 初始化配置失败", remedy: "请查看输出信息后重试" },
+  service_failed: { message: "ZeroClaw 服务操作失败", remedy: "请重试，或在设置中切换为内嵌 Gateway 模式" },
+  gateway_unreachable: { message: "无法连接到 Gateway", remedy: "请重启 Gateway，并检查端口是否被拦截" },
+  gateway_rejected: { message: "Gateway 返回错误", remedy: "请检查 ZeroClaw 配置中的模型服务设置" },
+  permission_denied: { message: "权限不足", remedy: "请确认 EasyClaw 有权读写 ZeroClaw 数据目录" },
+  port_in_use: { message: "没有可用的 Gateway 端口", remedy: "请关闭占用端口的程序，或在配置中指定其他端口" },
+  config_invalid: { message: "ZeroClaw 配置无效", remedy: "请修正提示的配置项，或从备份恢复配置" },
+  timeout: { message: "操作超时", remedy: "请重试；如果持续超时，请检查系统负载" },
+  not_found: { message: "未找到请求的内容", remedy: null },
+  invalid_input: { message: "请求无效", remedy: null },
+  internal: { message: "发生未知错误", remedy: null },
+};
+
+const HTTP_STATUS_MESSAGES: Record<number, string> = {
+  400: "请求参数错误",
+  401: "认证失败，请检查配置",
+  403: "访问被拒绝",
+  404: "接口不存在，请检查 Gateway 版本",
+  429: "请求过于频繁，请稍后重试",
+  500: "服务内部错误",
+  502: "网关错误",
+  503: "服务暂时不可用",
+};
+
+/** Explains an HTTP error status of the gateway, e.g. `服务内部错误 (500)`. */
+export function describeHttpStatus(status: number): string {
+  return `${HTTP_STATUS_MESSAGES[status] ?? "请求失败"} (${status})`;
+}
+
+export function isEasyClawError(err: unknown): err is EasyClawError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    typeof (err as EasyClawError).code === "string" &&
+    typeof (err as EasyClawError).detail === "string"
+  );
+}
+
+/** Localizes a rejected `invoke`. Anything that isn't an `EasyClawError` is shown as text. */
+export function describeError(err: unknown): ErrorDescription {
+  if (!isEasyClawError(err)) {
+    const message = err instanceof Error ? err.message : String(err);
+    return { code: null, message, detail: null, remedy: null, status: null };
+  }
+  const localized = MESSAGES[err.code];
+  return {
+    code: err.code,
+    message: localized?.message ?? err.message,
+    detail: err.detail || null,
+    remedy: localized ? localized.remedy : err.remedy,
+    status: err.status ?? null,
+  };
+}
@@ -1,12 +1,19 @@
 import { describe, it, expect, vi } from "vitest";
-import { mount } from "@vue/test-utils";
+import { mount, flushPromises } from "@vue/test-utils";
 import { createRouter, createMemoryHistory } from "vue-router";
 import AppLayout from "../../src/components/AppLayout.vue";
 
 vi.mock("@tauri-apps/api/core", () => ({
-  invoke: vi.fn().mockResolvedValue(false),
+  invoke: vi.fn().mockResolvedValue({ state: "stopped", last_error: null }),
 }));
 
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: vi.fn().mockResolvedValue(() => {}),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
 function createMockRouter() {
   return createRouter({
     history: createMemoryHistory(),
@@ -73,4 +80,44 @@ describe("AppLayout", () => {
     expect(root.classes()).toContain("flex");
     expect(root.classes()).toContain("flex-row");
   });
+
+  it("shows the gateway state from gateway_status", async () => {
+    vi.mocked(invoke).mockResolvedValueOnce({ state: "crashed", last_error: "exit 1" });
+    const router = createMockRouter();
+    const wrapper = mount(AppLayout, {
+      global: { plugins: [router] },
+    });
+    await flushPromises();
+    expect(wrapper.find("[data-testid='gateway-status']").text()).toContain("异常退出");
+  });
+
+  it("shows connected when the gateway is running", async () => {
+    vi.mocked(invoke).mockResolvedValueOnce({ state: "running", last_error: null });
+    const router = createMockRouter();
+    const wrapper = mount(AppLayout, {
+      global: { plugins: [router] },
+    });
+    await flushPromises();
+    expect(wrapper.find("[data-testid='gateway-status']").text()).toContain("已连接");
+  });
+
+  it("refreshes the gateway state on gateway://state-changed", async () => {
+    const router = createMockRouter();
+    const wrapper = mount(AppLayout, {
+      global: { plugins: [router] },
+    });
+    await flushPromises();
+    expect(listen).toHaveBeenCalledWith("gateway://state-changed", expect.any(Function));
+
+    const handler = vi.mocked(listen).mock.calls.at(-1)![1];
+    vi.mocked(invoke).mockResolvedValueOnce({ state: "stopped", last_error: null });
+    await handler({
+      event: "gateway://state-changed",
+      id: 1,
+      payload: { state: "restarting", reason: "Gateway is down: connection refused", attempt: 1 },
+    });
+    await flushPromises();
+
+    expect(wrapper.find("[data-testid='gateway-status']").text()).toContain("未启动");
+  });
 });
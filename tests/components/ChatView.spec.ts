@@ -7,6 +7,7 @@ import ChatView from "../../src/components/ChatView.vue";
 const mockSendMessage = vi.fn();
 const mockAbortResponse = vi.fn();
 const mockClearMessages = vi.fn();
+const mockRestoreConversation = vi.fn();
 const mockMessages = ref<Array<{
   id: string;
   role: string;
@@ -25,6 +26,7 @@ vi.mock("../../src/composables/useChat", () => ({
     sendMessage: mockSendMessage,
     abortResponse: mockAbortResponse,
     clearMessages: mockClearMessages,
+    restoreConversation: mockRestoreConversation,
   }),
 }));
 
@@ -47,6 +49,7 @@ describe("ChatView", () => {
     mockSendMessage.mockReset();
     mockAbortResponse.mockReset();
     mockClearMessages.mockReset();
+    mockRestoreConversation.mockReset();
   });
 
   it("renders empty state when no messages", async () => {
@@ -60,6 +63,16 @@ describe("ChatView", () => {
     expect(wrapper.text()).toContain("有什么可以帮您");
   });
 
+  it("restores the last conversation on mount", async () => {
+    const router = createMockRouter();
+    await router.push("/chat");
+    await router.isReady();
+    mount(ChatView, {
+      global: { plugins: [router] },
+    });
+    expect(mockRestoreConversation).toHaveBeenCalledOnce();
+  });
+
   it("renders the chat header with title and clear button", async () => {
     const router = createMockRouter();
     await router.push("/chat");
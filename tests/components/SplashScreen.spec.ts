@@ -84,4 +84,43 @@ describe("SplashScreen", () => {
     const progressBar = wrapper.find("[data-testid='progress-bar']");
     expect(progressBar.exists()).toBe(false);
   });
+
+  it("shows the latest process output line while initializing", () => {
+    const wrapper = mount(SplashScreen, {
+      props: {
+        status: "initializing",
+        progress: 30,
+        message: "正在初始化配置文件...",
+        error: null,
+        detail: "Writing config.toml",
+      },
+    });
+    expect(wrapper.find("[data-testid='process-output']").text()).toBe("Writing config.toml");
+  });
+
+  it("shows the failing output line on error", () => {
+    const wrapper = mount(SplashScreen, {
+      props: {
+        status: "error",
+        progress: 30,
+        message: "初始化失败",
+        error: "zeroclaw onboard failed (exit 1)",
+        detail: "error: permission denied",
+      },
+    });
+    expect(wrapper.find("[data-testid='process-output']").text()).toBe("error: permission denied");
+  });
+
+  it("shows the suggested remedy on error", () => {
+    const wrapper = mount(SplashScreen, {
+      props: {
+        status: "error",
+        progress: 30,
+        message: "初始化失败",
+        error: "没有可用的 Gateway 端口",
+        remedy: "请关闭占用端口的程序，或在配置中指定其他端口",
+      },
+    });
+    expect(wrapper.find("[data-testid='error-remedy']").text()).toBe("请关闭占用端口的程序，或在配置中指定其他端口");
+  });
 });
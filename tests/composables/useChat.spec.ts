@@ -1,23 +1,31 @@
-import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
+import { describe, it, expect, vi, beforeEach } from "vitest";
 import { nextTick } from "vue";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+  Channel: class {
+    onmessage: (event: unknown) => void = () => {};
+  },
+}));
+
+import { invoke } from "@tauri-apps/api/core";
 import { useChat } from "../../src/composables/useChat";
 
-function createJsonResponse(body: Record<string, unknown>, status = 200) {
-  return new Response(JSON.stringify(body), {
-    status,
-    headers: { "Content-Type": "application/json" },
-  });
+const mockedInvoke = vi.mocked(invoke);
+
+function gatewayError(code: string, detail: string, status?: number) {
+  return { code, message: "backend message", detail, remedy: null, status };
 }
 
-describe("useChat", () => {
-  let fetchSpy: ReturnType<typeof vi.spyOn>;
+function reply(content: string, streamed = false) {
+  return { content, streamed, cancelled: false };
+}
 
-  beforeEach(() => {
-    fetchSpy = vi.spyOn(globalThis, "fetch");
-  });
+type SendArgs = { requestId: string; onEvent: { onmessage: (event: unknown) => void } };
 
-  afterEach(() => {
-    fetchSpy.mockRestore();
+describe("useChat", () => {
+  beforeEach(() => {
+    mockedInvoke.mockReset();
   });
 
   it("has empty messages and idle state initially", () => {
@@ -28,9 +36,7 @@ describe("useChat", () => {
   });
 
   it("adds user message and sets loading on sendMessage", async () => {
-    fetchSpy.mockResolvedValue(
-      createJsonResponse({ reply: "Hi there" })
-    );
+    mockedInvoke.mockResolvedValue(reply("Hi there"));
 
     const { messages, isLoading, sendMessage } = useChat();
     const promise = sendMessage("Hello");
@@ -44,28 +50,21 @@ describe("useChat", () => {
     await promise;
   });
 
-  it("sends correct request to /webhook endpoint", async () => {
-    fetchSpy.mockResolvedValue(
-      createJsonResponse({ reply: "Hello" })
-    );
+  it("sends the message through the send_chat_message command", async () => {
+    mockedInvoke.mockResolvedValue(reply("Hello"));
 
     const { sendMessage } = useChat();
-    await sendMessage("Hi");
+    await sendMessage("  Hi  ");
 
-    expect(fetchSpy).toHaveBeenCalledWith(
-      "http://localhost:18789/webhook",
-      expect.objectContaining({
-        method: "POST",
-        headers: { "Content-Type": "application/json" },
-        body: JSON.stringify({ message: "Hi" }),
-      })
-    );
+    expect(invoke).toHaveBeenCalledWith("send_chat_message", {
+      requestId: expect.any(String),
+      message: "Hi",
+      onEvent: expect.anything(),
+    });
   });
 
-  it("parses assistant response from JSON reply field", async () => {
-    fetchSpy.mockResolvedValue(
-      createJsonResponse({ reply: "Hello World" })
-    );
+  it("adds the assistant reply", async () => {
+    mockedInvoke.mockResolvedValue(reply("Hello World"));
 
     const { messages, isLoading, sendMessage } = useChat();
     await sendMessage("Hi");
@@ -77,108 +76,174 @@ describe("useChat", () => {
     expect(isLoading.value).toBe(false);
   });
 
-  it("adds error assistant message on network failure", async () => {
-    fetchSpy.mockRejectedValue(new Error("Failed to fetch"));
+  it("appends streamed deltas to one assistant message", async () => {
+    let streamingContent: string | null = null;
+    mockedInvoke.mockImplementation(async (cmd, args) => {
+      if (cmd !== "send_chat_message") return null;
+      const { onEvent } = args as SendArgs;
+      onEvent.onmessage({ event: "delta", text: "Hel" });
+      onEvent.onmessage({ event: "delta", text: "lo" });
+      streamingContent = messages.value[1].content;
+      expect(messages.value[1].status).toBe("streaming");
+      return reply("Hello", true);
+    });
+
+    const { messages, isLoading, sendMessage } = useChat();
+    await sendMessage("Hi");
+
+    expect(streamingContent).toBe("Hello");
+    expect(messages.value.length).toBe(2);
+    expect(messages.value[1].content).toBe("Hello");
+    expect(messages.value[1].status).toBe("done");
+    expect(isLoading.value).toBe(false);
+  });
+
+  it("adds error assistant message when the gateway is unreachable", async () => {
+    mockedInvoke.mockRejectedValue(
+      gatewayError("gateway_unreachable", "Gateway not reachable at http://127.0.0.1:18789: connection refused")
+    );
 
     const { messages, error, isLoading, sendMessage } = useChat();
     await sendMessage("Hi");
 
     expect(error.value).toContain("无法连接到服务");
-    expect(error.value).toContain("Failed to fetch");
+    expect(error.value).toContain("connection refused");
     expect(isLoading.value).toBe(false);
     // User message + error assistant message
     expect(messages.value.length).toBe(2);
     expect(messages.value[1].role).toBe("assistant");
     expect(messages.value[1].status).toBe("error");
-    expect(messages.value[1].content).toContain("Failed to fetch");
+    expect(messages.value[1].content).toContain("connection refused");
   });
 
-  it("adds error assistant message on connection refused", async () => {
-    fetchSpy.mockRejectedValue(new TypeError("fetch failed"));
+  it("shows the gateway's own error message", async () => {
+    mockedInvoke.mockRejectedValue(gatewayError("gateway_rejected", "Unauthorized: pairing required"));
 
     const { messages, error, sendMessage } = useChat();
     await sendMessage("Hi");
 
-    expect(error.value).toContain("无法连接到服务");
+    expect(error.value).toBe("Unauthorized: pairing required");
     expect(messages.value.length).toBe(2);
-    expect(messages.value[1].role).toBe("assistant");
     expect(messages.value[1].status).toBe("error");
+    expect(messages.value[1].content).toBe("Unauthorized: pairing required");
   });
 
-  it("adds error assistant message on HTTP error with body", async () => {
-    fetchSpy.mockResolvedValue(
-      createJsonResponse({ error: "LLM provider rate limited" }, 429)
-    );
+  it("shows the HTTP status when the gateway gives no message", async () => {
+    mockedInvoke.mockRejectedValue(gatewayError("gateway_rejected", "Gateway returned HTTP 500", 500));
 
     const { messages, error, sendMessage } = useChat();
     await sendMessage("Hi");
 
-    expect(error.value).toBe("LLM provider rate limited");
-    expect(messages.value.length).toBe(2);
-    expect(messages.value[1].role).toBe("assistant");
+    expect(error.value).toBe("服务内部错误 (500)");
     expect(messages.value[1].status).toBe("error");
-    expect(messages.value[1].content).toBe("LLM provider rate limited");
   });
 
-  it("adds error assistant message on HTTP error without body", async () => {
-    fetchSpy.mockResolvedValue(
-      new Response("Internal Server Error", { status: 500 })
-    );
+  it("shows untyped errors as text", async () => {
+    mockedInvoke.mockRejectedValue("unexpected");
 
     const { messages, error, sendMessage } = useChat();
     await sendMessage("Hi");
 
-    expect(error.value).toContain("500");
-    expect(messages.value.length).toBe(2);
-    expect(messages.value[1].role).toBe("assistant");
+    expect(error.value).toBe("unexpected");
     expect(messages.value[1].status).toBe("error");
-    expect(messages.value[1].content).toContain("500");
   });
 
-  it("adds error assistant message on HTTP 401 unauthorized", async () => {
-    fetchSpy.mockResolvedValue(
-      createJsonResponse({ error: "Unauthorized: pairing required" }, 401)
-    );
+  it("cancels the running request on abort and keeps the partial reply", async () => {
+    let finish: () => void = () => {};
+    mockedInvoke.mockImplementation(async (cmd, args) => {
+      if (cmd !== "send_chat_message") return true;
+      const { onEvent } = args as SendArgs;
+      onEvent.onmessage({ event: "delta", text: "Hel" });
+      await new Promise<void>((resolve) => (finish = resolve));
+      onEvent.onmessage({ event: "delta", text: "lo" });
+      return { content: "Hel", streamed: true, cancelled: true };
+    });
+
+    const { messages, isLoading, sendMessage, abortResponse } = useChat();
+    const promise = sendMessage("Hi");
+    await nextTick();
+    const sendCall = mockedInvoke.mock.calls.find(([cmd]) => cmd === "send_chat_message")!;
+    const { requestId } = sendCall[1] as SendArgs;
 
-    const { messages, error, sendMessage } = useChat();
-    await sendMessage("Hi");
+    abortResponse();
+    expect(invoke).toHaveBeenCalledWith("cancel_chat_message", { requestId });
+    expect(isLoading.value).toBe(false);
+    expect(messages.value[1].status).toBe("done");
 
-    expect(error.value).toBe("Unauthorized: pairing required");
-    expect(messages.value[1].status).toBe("error");
+    finish();
+    await promise;
+    expect(messages.value.length).toBe(2);
+    expect(messages.value[1].content).toBe("Hel");
   });
 
-  it("adds error assistant message on JSON parse failure", async () => {
-    fetchSpy.mockResolvedValue(
-      new Response("not json", {
-        status: 200,
-        headers: { "Content-Type": "application/json" },
-      })
-    );
+  it("saves each finished message to the conversation", async () => {
+    mockedInvoke.mockResolvedValue(reply("Hello World"));
 
-    const { messages, error, sendMessage } = useChat();
+    const { conversationId, sendMessage } = useChat();
     await sendMessage("Hi");
+    await new Promise((resolve) => setTimeout(resolve, 0));
 
-    expect(messages.value.length).toBe(2);
-    expect(messages.value[1].status).toBe("error");
-    expect(error.value).toBeTruthy();
+    const saved = mockedInvoke.mock.calls.filter(([cmd]) => cmd === "append_message").map(([, args]) => args);
+    expect(saved).toEqual([
+      { id: conversationId.value, message: expect.objectContaining({ role: "user", content: "Hi", status: "done" }) },
+      { id: conversationId.value, message: expect.objectContaining({ role: "assistant", content: "Hello World", status: "done" }) },
+    ]);
   });
 
-  it("can clear all messages", async () => {
-    fetchSpy.mockResolvedValue(
-      createJsonResponse({ reply: "Reply" })
-    );
+  it("restores the latest conversation", async () => {
+    mockedInvoke.mockImplementation(async (cmd) => {
+      if (cmd === "list_conversations") return [{ id: "c2", title: "Latest" }, { id: "c1", title: "Older" }];
+      return {
+        id: "c2",
+        title: "Latest",
+        messages: [{ id: "m1", role: "user", content: "Hi", timestamp: 1, status: "done" }],
+      };
+    });
+
+    const { messages, conversationId, restoreConversation } = useChat();
+    await restoreConversation();
+
+    expect(invoke).toHaveBeenCalledWith("load_conversation", { id: "c2" });
+    expect(conversationId.value).toBe("c2");
+    expect(messages.value).toEqual([{ id: "m1", role: "user", content: "Hi", timestamp: 1, status: "done" }]);
+  });
+
+  it("starts a new conversation when clearing and keeps the saved one", async () => {
+    mockedInvoke.mockResolvedValue(reply("Reply"));
 
-    const { messages, clearMessages, sendMessage } = useChat();
+    const { messages, conversationId, clearMessages, sendMessage } = useChat();
     await sendMessage("Hello");
     expect(messages.value.length).toBe(2);
+    const id = conversationId.value;
 
     clearMessages();
+    await new Promise((resolve) => setTimeout(resolve, 0));
+    expect(messages.value).toEqual([]);
+    expect(conversationId.value).toBeNull();
+    expect(invoke).not.toHaveBeenCalledWith("delete_conversation", expect.anything());
+
+    await sendMessage("Again");
+    expect(conversationId.value).not.toBeNull();
+    expect(conversationId.value).not.toBe(id);
+  });
+
+  it("deletes the current conversation and clears the chat", async () => {
+    mockedInvoke.mockResolvedValue(reply("Reply"));
+
+    const { messages, conversationId, deleteConversation, sendMessage } = useChat();
+    await sendMessage("Hello");
+    const id = conversationId.value!;
+
+    mockedInvoke.mockResolvedValue(undefined);
+    await deleteConversation(id);
     expect(messages.value).toEqual([]);
+    expect(conversationId.value).toBeNull();
+    expect(invoke).toHaveBeenCalledWith("delete_conversation", { id });
   });
 
   it("does not send empty messages", async () => {
     const { sendMessage } = useChat();
     await sendMessage("   ");
-    expect(fetchSpy).not.toHaveBeenCalled();
+    expect(invoke).not.toHaveBeenCalled();
   });
 });
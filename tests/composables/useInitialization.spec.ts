@@ -5,10 +5,16 @@ vi.mock("@tauri-apps/api/core", () => ({
   invoke: vi.fn(),
 }));
 
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: vi.fn().mockResolvedValue(() => {}),
+}));
+
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { useInitialization } from "../../src/composables/useInitialization";
 
 const mockedInvoke = vi.mocked(invoke);
+const mockedListen = vi.mocked(listen);
 
 describe("useInitialization", () => {
   beforeEach(() => {
@@ -33,6 +39,31 @@ describe("useInitialization", () => {
     expect(state.value.progress).toBe(100);
   });
 
+  it("should restart the gateway after upgrading the bundled binary", async () => {
+    mockedInvoke
+      .mockResolvedValueOnce(true) // check_initialized
+      .mockResolvedValueOnce({ action: "upgraded", from: "0.1.0", to: "0.2.0" }) // sync_zeroclaw_binary
+      .mockResolvedValueOnce("OK"); // restart_gateway
+
+    const { state, checkAndInitialize } = useInitialization();
+    await checkAndInitialize();
+
+    expect(mockedInvoke).toHaveBeenCalledWith("sync_zeroclaw_binary");
+    expect(mockedInvoke).toHaveBeenCalledWith("restart_gateway");
+    expect(state.value.status).toBe("success");
+  });
+
+  it("should not restart the gateway when the binary is up to date", async () => {
+    mockedInvoke
+      .mockResolvedValueOnce(true) // check_initialized
+      .mockResolvedValueOnce({ action: "up_to_date", installed: "0.1.0", bundled: "0.1.0" });
+
+    const { checkAndInitialize } = useInitialization();
+    await checkAndInitialize();
+
+    expect(mockedInvoke).not.toHaveBeenCalledWith("restart_gateway");
+  });
+
   it("should run initialization if not yet initialized", async () => {
     mockedInvoke
       .mockResolvedValueOnce(false) // check_initialized returns false
@@ -59,6 +90,26 @@ describe("useInitialization", () => {
 
     expect(state.value.status).toBe("error");
     expect(state.value.error).toBe("Init failed");
+    expect(state.value.errorCode).toBeNull();
+  });
+
+  it("should localize typed initialization errors", async () => {
+    mockedInvoke
+      .mockResolvedValueOnce(false) // check_initialized
+      .mockRejectedValueOnce({
+        code: "permission_denied",
+        message: "Permission denied",
+        detail: "Failed to create /opt/zc: Permission denied (os error 13)",
+        remedy: "Make sure EasyClaw may read and write the ZeroClaw directory",
+      }); // initialize_zeroclaw
+
+    const { state, checkAndInitialize } = useInitialization();
+    await checkAndInitialize();
+
+    expect(state.value.status).toBe("error");
+    expect(state.value.errorCode).toBe("permission_denied");
+    expect(state.value.error).toBe("权限不足: Failed to create /opt/zc: Permission denied (os error 13)");
+    expect(state.value.remedy).toBe("请确认 EasyClaw 有权读写 ZeroClaw 数据目录");
   });
 
   it("should handle gateway failure gracefully (not block success)", async () => {
@@ -91,4 +142,33 @@ describe("useInitialization", () => {
     await retry();
     expect(state.value.status).toBe("success");
   });
+
+  it("collects process output streamed during initialization", async () => {
+    const unlisten = vi.fn();
+    mockedListen.mockImplementationOnce(async (_event, handler) => {
+      mockedInvoke.mockImplementation(async (cmd) => {
+        if (cmd === "initialize_zeroclaw") {
+          handler({
+            event: "process://output",
+            id: 1,
+            payload: { step: "onboard", stream: "stdout", line: "Writing config.toml" },
+          });
+          return "OK";
+        }
+        return cmd === "check_initialized" ? false : "OK";
+      });
+      return unlisten;
+    });
+
+    const { output, lastOutputLine, checkAndInitialize } = useInitialization();
+    await checkAndInitialize();
+
+    expect(mockedListen).toHaveBeenCalledWith("process://output", expect.any(Function));
+    expect(output.value).toEqual([
+      { step: "onboard", stream: "stdout", line: "Writing config.toml" },
+    ]);
+    expect(lastOutputLine.value).toBe("Writing config.toml");
+    expect(unlisten).toHaveBeenCalled();
+    mockedInvoke.mockReset();
+  });
 });
@@ -0,0 +1,54 @@
+import { describe, it, expect } from "vitest";
+import { describeError, describeHttpStatus, isEasyClawError } from "../../src/utils/errors";
+
+describe("describeError", () => {
+  it("localizes typed errors by code", () => {
+    const description = describeError({
+      code: "binary_missing",
+      message: "ZeroClaw is not installed",
+      detail: "No zeroclaw binary installed or on PATH",
+      remedy: "Run the initialization again to install ZeroClaw",
+    });
+
+    expect(description).toEqual({
+      code: "binary_missing",
+      message: "未找到 ZeroClaw 程序",
+      detail: "No zeroclaw binary installed or on PATH",
+      remedy: "请重新运行初始化以安装 ZeroClaw",
+      status: null,
+    });
+  });
+
+  it("falls back to the backend text for unknown codes", () => {
+    const description = describeError({
+      code: "something_new",
+      message: "Something new happened",
+      detail: "",
+      remedy: "Try again",
+    });
+
+    expect(description.message).toBe("Something new happened");
+    expect(description.detail).toBeNull();
+    expect(description.remedy).toBe("Try again");
+  });
+
+  it("passes on the HTTP status of gateway rejections", () => {
+    const description = describeError({
+      code: "gateway_rejected",
+      message: "ZeroClaw gateway returned an error",
+      detail: "Gateway returned HTTP 429",
+      remedy: null,
+      status: 429,
+    });
+
+    expect(description.status).toBe(429);
+    expect(describeHttpStatus(429)).toBe("请求过于频繁，请稍后重试 (429)");
+    expect(describeHttpStatus(418)).toBe("请求失败 (418)");
+  });
+
+  it("shows plain errors as text", () => {
+    expect(describeError("Init failed")).toEqual({ code: null, message: "Init failed", detail: null, remedy: null, status: null });
+    expect(describeError(new Error("boom")).message).toBe("boom");
+    expect(isEasyClawError({ code: "timeout" })).toBe(false);
+  });
+});